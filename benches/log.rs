@@ -0,0 +1,124 @@
+#[macro_use]
+extern crate criterion;
+extern crate log;
+extern crate mowl;
+extern crate term;
+
+use criterion::Criterion;
+use log::{Level, LevelFilter, Log, Record};
+use mowl::{LoggerBuilder, Output, SharedWriter};
+use std::{env, io};
+
+#[cfg(unix)]
+const NULL: &str = "/dev/null";
+#[cfg(windows)]
+const NULL: &str = "NUL";
+
+/// The size at which the memory output is cleared to keep the memory bounded.
+const MEMORY_LIMIT: usize = 1 << 20;
+
+/// An output writing into a `Vec<u8>` to remove the I/O variability.
+struct MemoryWriter(Vec<u8>);
+
+impl io::Write for MemoryWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0.len() > MEMORY_LIMIT {
+            self.0.clear();
+        }
+        self.0.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn memory_logger(builder: LoggerBuilder) -> mowl::Logger {
+    let writer = SharedWriter::new(MemoryWriter(Vec::with_capacity(MEMORY_LIMIT)));
+    builder.output(Output::Writer(writer)).build().unwrap()
+}
+
+fn bench_record(c: &mut Criterion, name: &str, logger: &mowl::Logger) {
+    let record = Record::builder()
+        .args(format_args!("A benchmarked message"))
+        .level(Level::Info)
+        .module_path(Some("bench"))
+        .build();
+    c.bench_function(name, |b| b.iter(|| logger.log(&record)));
+}
+
+fn log_to_memory(c: &mut Criterion) {
+    let logger = memory_logger(LoggerBuilder::new().color_mode(mowl::ColorMode::Always));
+    bench_record(c, "log to memory", &logger);
+}
+
+#[cfg(feature = "json")]
+fn log_json_to_memory(c: &mut Criterion) {
+    let logger = memory_logger(LoggerBuilder::new().format(mowl::Format::Json));
+    bench_record(c, "log json to memory", &logger);
+}
+
+#[cfg(not(feature = "json"))]
+fn log_json_to_memory(_: &mut Criterion) {}
+
+fn log_no_color_to_memory(c: &mut Criterion) {
+    env::set_var("NO_COLOR", "1");
+    let logger = memory_logger(LoggerBuilder::new().color_mode(mowl::ColorMode::Auto));
+    bench_record(c, "log no color to memory", &logger);
+    env::remove_var("NO_COLOR");
+}
+
+/// The baseline of a record which is discarded by its level.
+fn log_disabled(c: &mut Criterion) {
+    let logger = memory_logger(LoggerBuilder::new().level(LevelFilter::Error));
+    bench_record(c, "log disabled", &logger);
+}
+
+fn log_record(c: &mut Criterion) {
+    let logger = mowl::LoggerBuilder::new()
+        .file(NULL)
+        .color_mode(mowl::ColorMode::Always)
+        .build()
+        .unwrap();
+    let record = Record::builder()
+        .args(format_args!("A benchmarked message"))
+        .level(Level::Info)
+        .module_path(Some("bench"))
+        .build();
+    c.bench_function("log record", |b| b.iter(|| logger.log(&record)));
+}
+
+/// Compare with the `fast-format` feature enabled and disabled.
+fn log_formatted_record(c: &mut Criterion) {
+    let logger = mowl::LoggerBuilder::new().file(NULL).build().unwrap();
+    let value = 42;
+    c.bench_function("log formatted record", |b| {
+        b.iter(|| {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("A benchmarked message with {}", value))
+                    .level(Level::Info)
+                    .module_path(Some("bench"))
+                    .build(),
+            )
+        })
+    });
+}
+
+/// The cost which was paid per record before the sink became persistent.
+fn create_terminal(c: &mut Criterion) {
+    c.bench_function("create terminal", |b| b.iter(term::stderr));
+}
+
+criterion_group!(
+    benches,
+    log_record,
+    log_formatted_record,
+    log_to_memory,
+    log_json_to_memory,
+    log_no_color_to_memory,
+    log_disabled,
+    create_terminal
+);
+criterion_main!(benches);
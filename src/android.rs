@@ -0,0 +1,81 @@
+//! Logging to the Android log buffer, which is shown by `logcat`
+use error::Error;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{
+    ffi::CString,
+    os::raw::{c_char, c_int},
+};
+
+#[link(name = "log")]
+extern "C" {
+    fn __android_log_write(prio: c_int, tag: *const c_char, text: *const c_char) -> c_int;
+}
+
+/// The priorities of `android/log.h`.
+const ANDROID_LOG_VERBOSE: c_int = 2;
+const ANDROID_LOG_DEBUG: c_int = 3;
+const ANDROID_LOG_INFO: c_int = 4;
+const ANDROID_LOG_WARN: c_int = 5;
+const ANDROID_LOG_ERROR: c_int = 6;
+
+/// Installs an `AndroidLogger` with the tag as global logger.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+pub fn init_android(tag: &str, level: LevelFilter) -> Result<(), Error> {
+    let logger = AndroidLogger::new(tag, level);
+    ::set_boxed_logger(Box::new(logger), level)
+}
+
+/// A logger which writes each record with a tag to the Android log buffer.
+#[derive(Debug)]
+pub struct AndroidLogger {
+    level: LevelFilter,
+    tag: CString,
+}
+
+impl AndroidLogger {
+    /// Creates a logger for the tag, which is shown next to each record.
+    /// Null bytes are removed from the tag.
+    pub fn new(tag: &str, level: LevelFilter) -> Self {
+        Self {
+            level,
+            tag: c_string(tag),
+        }
+    }
+}
+
+impl Log for AndroidLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            let text = c_string(&record.args().to_string());
+            // SAFETY: Both strings are null terminated and outlive the call
+            unsafe {
+                __android_log_write(priority(record.level()), self.tag.as_ptr(), text.as_ptr());
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The Android log priority of a log level.
+fn priority(level: Level) -> c_int {
+    match level {
+        Level::Error => ANDROID_LOG_ERROR,
+        Level::Warn => ANDROID_LOG_WARN,
+        Level::Info => ANDROID_LOG_INFO,
+        Level::Debug => ANDROID_LOG_DEBUG,
+        Level::Trace => ANDROID_LOG_VERBOSE,
+    }
+}
+
+/// Converts the string into a C string without interior null bytes.
+fn c_string(s: &str) -> CString {
+    CString::new(s.replace('\0', "")).unwrap_or_default()
+}
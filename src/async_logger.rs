@@ -0,0 +1,184 @@
+//! Non-blocking dispatch of log records via a background thread
+//!
+//! The records are rendered on the calling thread and written by a background
+//! thread, which keeps slow outputs off the hot path.
+//!
+//! ```
+//! # #[macro_use] extern crate log;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! mowl::async_logger::init(log::LevelFilter::Info).unwrap();
+//! info!("Written in the background");
+//! log::logger().flush();
+//! # }
+//! ```
+use error::{self, Error};
+use log::{Level, LevelFilter};
+use sink::LogSink;
+use std::{
+    collections::VecDeque,
+    io::Write,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    thread::{self, JoinHandle},
+};
+use LoggerBuilder;
+
+/// The default number of records which can be queued.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Installs a logger writing to stderr from a background thread, which blocks
+/// the caller if more than `DEFAULT_CAPACITY` records are queued.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+pub fn init(level: LevelFilter) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(level)
+        .async_dispatch(DEFAULT_CAPACITY, OverflowPolicy::default())
+        .init()
+}
+
+/// What happens with a record if the queue of the background thread is full.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OverflowPolicy {
+    /// Discard the new record
+    Drop,
+    /// Wait until the background thread made room, which is the default
+    #[default]
+    Block,
+    /// Discard the oldest queued record in favor of the new one
+    DropOldest,
+}
+
+/// The queue shared with the background thread. A `VecDeque` behind a mutex
+/// is used instead of a channel, because the sender cannot discard the oldest
+/// record of a channel.
+struct Queue {
+    state: Mutex<State>,
+    /// Signals new records or the shutdown to the background thread
+    filled: Condvar,
+    /// Signals taken or written records to waiting senders
+    drained: Condvar,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+struct State {
+    records: VecDeque<(Level, Vec<u8>)>,
+    writing: bool,
+    closed: bool,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn wait<'a>(&self, condvar: &Condvar, state: MutexGuard<'a, State>) -> MutexGuard<'a, State> {
+        condvar.wait(state).unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Hands rendered records over to a background thread writing them into the
+/// sink.
+pub(crate) struct Dispatcher {
+    queue: Arc<Queue>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Dispatcher {
+    /// Spawns the background thread writing into the sink.
+    pub(crate) fn spawn(
+        sink: Arc<Mutex<LogSink>>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<Self, Error> {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(State {
+                records: VecDeque::new(),
+                writing: false,
+                closed: false,
+            }),
+            filled: Condvar::new(),
+            drained: Condvar::new(),
+            capacity: capacity.max(1),
+            policy,
+        });
+        let worker = queue.clone();
+        let thread = thread::Builder::new()
+            .name("mowl".to_owned())
+            .spawn(move || write_records(&worker, &sink))?;
+        Ok(Self {
+            queue,
+            thread: Some(thread),
+        })
+    }
+
+    /// Queues a rendered record according to the overflow policy.
+    pub(crate) fn send(&self, level: Level, record: Vec<u8>) {
+        let queue = &*self.queue;
+        let mut state = queue.lock();
+        while state.records.len() >= queue.capacity {
+            match queue.policy {
+                OverflowPolicy::Drop => return,
+                OverflowPolicy::Block => state = queue.wait(&queue.drained, state),
+                OverflowPolicy::DropOldest => {
+                    state.records.pop_front();
+                }
+            }
+        }
+        state.records.push_back((level, record));
+        queue.filled.notify_one();
+    }
+
+    /// Blocks until all queued records are written.
+    pub(crate) fn wait(&self) {
+        let queue = &*self.queue;
+        let mut state = queue.lock();
+        while !state.records.is_empty() || state.writing {
+            state = queue.wait(&queue.drained, state);
+        }
+    }
+}
+
+impl Drop for Dispatcher {
+    fn drop(&mut self) {
+        self.queue.lock().closed = true;
+        self.queue.filled.notify_one();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The loop of the background thread, which writes all records until the
+/// dispatcher is dropped.
+fn write_records(queue: &Queue, sink: &Mutex<LogSink>) {
+    loop {
+        let mut state = queue.lock();
+        while state.records.is_empty() && !state.closed {
+            state = queue.wait(&queue.filled, state);
+        }
+        let (level, record) = match state.records.pop_front() {
+            Some(record) => record,
+            None => return,
+        };
+        state.writing = true;
+        drop(state);
+        queue.drained.notify_all();
+
+        let mut t = sink.lock().unwrap_or_else(PoisonError::into_inner);
+        #[cfg(all(unix, feature = "unix"))]
+        t.start_record(level);
+        if let Err(e) = t.write_all(&record).and_then(|()| t.end_record()) {
+            // The module is not known anymore after rendering the record
+            error::report_failure(level, "?", e);
+        }
+        drop(t);
+
+        queue.lock().writing = false;
+        queue.drained.notify_all();
+    }
+}
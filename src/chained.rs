@@ -0,0 +1,77 @@
+//! Delegation to one of two loggers
+use error::Error;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::io::{self, IsTerminal};
+
+/// Installs a `ChainedLogger` which uses the `primary` logger if stderr is a
+/// terminal and the `fallback` logger otherwise.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// let primary = mowl::LoggerBuilder::new().build().unwrap();
+/// let path = std::env::temp_dir().join("mowl-chained.log");
+/// let fallback = mowl::LoggerBuilder::new().file(path).build().unwrap();
+/// mowl::init_chained(Box::new(primary), Box::new(fallback)).unwrap();
+///
+/// info!("Written to the terminal or the file");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+pub fn init_chained(primary: Box<dyn Log>, fallback: Box<dyn Log>) -> Result<(), Error> {
+    let logger = ChainedLogger::use_fallback_when_no_tty(primary, fallback);
+    ::set_boxed_logger(Box::new(logger), LevelFilter::Trace)
+}
+
+/// A logger which delegates all records either to a primary or a fallback
+/// logger. Both loggers filter the records on their own.
+pub struct ChainedLogger {
+    primary: Box<dyn Log>,
+    fallback: Box<dyn Log>,
+    use_fallback: bool,
+}
+
+impl ChainedLogger {
+    /// Creates a logger which delegates to `fallback` if `use_fallback` is set
+    /// and to `primary` otherwise.
+    pub fn new(primary: Box<dyn Log>, fallback: Box<dyn Log>, use_fallback: bool) -> Self {
+        Self {
+            primary,
+            fallback,
+            use_fallback,
+        }
+    }
+
+    /// Creates a logger which delegates to `fallback` if stderr is not a
+    /// terminal, for example if it is redirected into a file.
+    pub fn use_fallback_when_no_tty(primary: Box<dyn Log>, fallback: Box<dyn Log>) -> Self {
+        Self::new(primary, fallback, !io::stderr().is_terminal())
+    }
+
+    fn active(&self) -> &dyn Log {
+        if self.use_fallback {
+            &*self.fallback
+        } else {
+            &*self.primary
+        }
+    }
+}
+
+impl Log for ChainedLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.active().enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.active().log(record)
+    }
+
+    fn flush(&self) {
+        self.active().flush()
+    }
+}
@@ -0,0 +1,273 @@
+//! The wall-clock time of the timestamps
+//!
+//! Custom timestamp formats are formatted via `chrono` or `time` if the
+//! `chrono` or `time-compat` feature is enabled, and with a built-in subset of
+//! the strftime specifiers in UTC otherwise.
+#[cfg(feature = "chrono")]
+use chrono::{
+    format::{Item, StrftimeItems},
+    Local, SecondsFormat, Utc,
+};
+use std::{
+    fmt::Write,
+    time::{SystemTime, UNIX_EPOCH},
+};
+#[cfg(all(feature = "time-compat", not(feature = "chrono")))]
+use time::OffsetDateTime;
+use TimeZone;
+
+/// The names of the weekdays, starting on Sunday.
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// The names of the months, starting on January.
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+/// A point in time broken down into its calendar fields in UTC.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct DateTime {
+    year: i64,
+    month: u32,
+    day: u32,
+    hour: u32,
+    minute: u32,
+    second: u32,
+    nanosecond: u32,
+    weekday: u32,
+    ordinal: u32,
+}
+
+impl DateTime {
+    /// The current time.
+    pub(crate) fn now() -> Self {
+        Self::from(SystemTime::now())
+    }
+
+    /// The date like `2024-06-15`.
+    pub(crate) fn date(&self) -> String {
+        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+
+    /// The nanoseconds within the second.
+    pub(crate) fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Formats the time with the built-in subset of the strftime specifiers.
+    pub(crate) fn format(&self, format: &str) -> String {
+        let mut result = String::with_capacity(format.len() * 2);
+        let mut chars = format.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                result.push(c);
+                continue;
+            }
+            // Writing into a string cannot fail
+            let _ = match chars.next() {
+                Some('a') => write!(result, "{}", &WEEKDAYS[self.weekday as usize][..3]),
+                Some('A') => write!(result, "{}", WEEKDAYS[self.weekday as usize]),
+                Some('b') => write!(result, "{}", &MONTHS[self.month as usize - 1][..3]),
+                Some('B') => write!(result, "{}", MONTHS[self.month as usize - 1]),
+                Some('d') => write!(result, "{:02}", self.day),
+                Some('e') => write!(result, "{:2}", self.day),
+                Some('F') => write!(result, "{}", self.date()),
+                Some('H') => write!(result, "{:02}", self.hour),
+                Some('j') => write!(result, "{:03}", self.ordinal),
+                Some('m') => write!(result, "{:02}", self.month),
+                Some('M') => write!(result, "{:02}", self.minute),
+                Some('N') => write!(result, "{:09}", self.nanosecond),
+                Some('.') => match fraction_digits(&mut chars) {
+                    Some(digits) => write!(
+                        result,
+                        ".{:01$}",
+                        self.nanosecond / 10u32.pow(9 - digits as u32),
+                        digits
+                    ),
+                    None => write!(result, "%."),
+                },
+                Some('S') => write!(result, "{:02}", self.second),
+                Some('T') => write!(
+                    result,
+                    "{:02}:{:02}:{:02}",
+                    self.hour, self.minute, self.second
+                ),
+                Some('y') => write!(result, "{:02}", self.year.rem_euclid(100)),
+                Some('Y') => write!(result, "{}", self.year),
+                Some('z') => write!(result, "+0000"),
+                Some('%') => write!(result, "%"),
+                Some(other) => write!(result, "%{}", other),
+                None => write!(result, "%"),
+            };
+        }
+        result
+    }
+}
+
+impl From<SystemTime> for DateTime {
+    fn from(time: SystemTime) -> Self {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let seconds = since_epoch.as_secs();
+        let days = (seconds / 86_400) as i64;
+        let time_of_day = (seconds % 86_400) as u32;
+
+        // The civil date of the days since the epoch, see
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let mp = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let mut ordinal =
+            [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334][month as usize - 1] + day;
+        if leap && month > 2 {
+            ordinal += 1;
+        }
+        DateTime {
+            year,
+            month,
+            day,
+            hour: time_of_day / 3600,
+            minute: time_of_day % 3600 / 60,
+            second: time_of_day % 60,
+            nanosecond: since_epoch.subsec_nanos(),
+            // The epoch was a Thursday
+            weekday: ((days + 4).rem_euclid(7)) as u32,
+            ordinal,
+        }
+    }
+}
+
+/// Consumes the `3f`, `6f` or `9f` of the fractional seconds specifiers
+/// `%.3f`, `%.6f` and `%.9f` and returns their number of digits.
+fn fraction_digits(chars: &mut std::str::Chars) -> Option<usize> {
+    let mut lookahead = chars.clone();
+    let digits = match (lookahead.next(), lookahead.next()) {
+        (Some('3'), Some('f')) => 3,
+        (Some('6'), Some('f')) => 6,
+        (Some('9'), Some('f')) => 9,
+        _ => return None,
+    };
+    *chars = lookahead;
+    Some(digits)
+}
+
+/// Checks whether the string is a date like `2024-06-15`.
+pub(crate) fn is_date(s: &str) -> bool {
+    let parts = s.split('-').collect::<Vec<_>>();
+    let number = |part: &str, len: usize, max: u32| {
+        part.len() == len
+            && part.bytes().all(|b| b.is_ascii_digit())
+            && part.parse::<u32>().is_ok_and(|n| (1..=max).contains(&n))
+    };
+    parts.len() == 3
+        && number(parts[0], 4, 9999)
+        && number(parts[1], 2, 12)
+        && number(parts[2], 2, 31)
+}
+
+#[cfg(feature = "chrono")]
+/// Checks whether the timestamp format is valid.
+pub(crate) fn validate_format(format: &str) -> Result<(), String> {
+    if StrftimeItems::new(format).any(|item| item == Item::Error) {
+        return Err("invalid format specifier".to_owned());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "chrono")]
+/// Formats the current time with the format or as RFC 3339 timestamp.
+pub(crate) fn timestamp(format: Option<&str>, timezone: TimeZone) -> String {
+    match timezone {
+        TimeZone::Utc => format_chrono(Utc::now(), format),
+        TimeZone::Local => format_chrono(Local::now(), format),
+    }
+}
+
+#[cfg(feature = "chrono")]
+fn format_chrono<Tz: chrono::TimeZone>(now: chrono::DateTime<Tz>, format: Option<&str>) -> String
+where
+    Tz::Offset: std::fmt::Display,
+{
+    match format {
+        Some(format) => now.format(format).to_string(),
+        None => now.to_rfc3339_opts(SecondsFormat::Secs, false),
+    }
+}
+
+#[cfg(all(feature = "time-compat", not(feature = "chrono")))]
+/// Checks whether the timestamp format is valid.
+pub(crate) fn validate_format(format: &str) -> Result<(), String> {
+    time::validate_format_string(format)
+}
+
+#[cfg(all(feature = "time-compat", not(feature = "chrono")))]
+/// Formats the current time with the format or as RFC 3339 timestamp.
+pub(crate) fn timestamp(format: Option<&str>, timezone: TimeZone) -> String {
+    let now = match timezone {
+        TimeZone::Utc => OffsetDateTime::now_utc(),
+        TimeZone::Local => {
+            OffsetDateTime::try_now_local().unwrap_or_else(|_| OffsetDateTime::now_utc())
+        }
+    };
+    match format {
+        Some(format) => now.format(format),
+        None => now.format(time::Format::Rfc3339),
+    }
+}
+
+#[cfg(not(any(feature = "chrono", feature = "time-compat")))]
+/// Checks whether all specifiers of the format are supported by the built-in
+/// formatting: `%a %A %b %B %d %e %F %H %j %m %M %N %S %T %y %Y %z %%` and
+/// the fractional seconds `%.3f %.6f %.9f`.
+pub(crate) fn validate_format(format: &str) -> Result<(), String> {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        match chars.next() {
+            Some(
+                'a' | 'A' | 'b' | 'B' | 'd' | 'e' | 'F' | 'H' | 'j' | 'm' | 'M' | 'N' | 'S' | 'T'
+                | 'y' | 'Y' | 'z' | '%',
+            ) => {}
+            Some('.') if fraction_digits(&mut chars).is_some() => {}
+            Some(other) => return Err(format!("unsupported specifier '%{}'", other)),
+            None => return Err("incomplete specifier at the end".to_owned()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(any(feature = "chrono", feature = "time-compat")))]
+/// Formats the current time with the format or as RFC 3339 timestamp. The
+/// local time zone is unknown without `chrono` or `time`, so UTC is used.
+pub(crate) fn timestamp(format: Option<&str>, _: TimeZone) -> String {
+    DateTime::now().format(format.unwrap_or("%FT%T+00:00"))
+}
@@ -0,0 +1,128 @@
+//! Colors of the log output beyond the named `term` colors
+use std::env;
+use term::color::*;
+
+/// A color of the log output.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ColorSpec {
+    /// One of the 16 named colors of the `term` crate, like
+    /// `term::color::MAGENTA`
+    Named(Color),
+    /// A color of the 256 color palette, which is written as raw ANSI escape
+    /// sequence and approximated by a named color if the terminal does not
+    /// support it
+    Color256(u8),
+    #[cfg(feature = "truecolor")]
+    /// A 24-bit RGB color, which is written as raw ANSI escape sequence if the
+    /// terminal advertises `COLORTERM=truecolor` and approximated otherwise
+    Rgb(u8, u8, u8),
+}
+
+/// The colors supported by the terminal.
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+pub(crate) enum ColorDepth {
+    /// The 16 named colors
+    Named,
+    /// The 256 color palette
+    Palette,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+impl From<Color> for ColorSpec {
+    fn from(color: Color) -> Self {
+        ColorSpec::Named(color)
+    }
+}
+
+impl ColorSpec {
+    /// Replaces colors which are not supported by the terminal with the
+    /// closest supported color.
+    pub(crate) fn resolve(self, depth: ColorDepth) -> Self {
+        match self {
+            ColorSpec::Color256(n) if depth < ColorDepth::Palette => {
+                ColorSpec::Named(approximate(n))
+            }
+            #[cfg(feature = "truecolor")]
+            ColorSpec::Rgb(r, g, b) if depth < ColorDepth::TrueColor => {
+                ColorSpec::Color256(rgb_to_palette(r, g, b)).resolve(depth)
+            }
+            _ => self,
+        }
+    }
+
+    /// The ANSI escape sequence to set the color as foreground.
+    pub(crate) fn ansi_fg(self) -> String {
+        self.ansi(30)
+    }
+
+    /// The ANSI escape sequence to set the color as background.
+    pub(crate) fn ansi_bg(self) -> String {
+        self.ansi(40)
+    }
+
+    /// The ANSI escape sequence, where `base` is 30 for the foreground and 40
+    /// for the background.
+    fn ansi(self, base: u32) -> String {
+        match self {
+            ColorSpec::Named(color) => format!("\x1b[{}m", ansi_code(base, color)),
+            ColorSpec::Color256(n) => format!("\x1b[{};5;{}m", base + 8, n),
+            #[cfg(feature = "truecolor")]
+            ColorSpec::Rgb(r, g, b) => format!("\x1b[{};2;{};{};{}m", base + 8, r, g, b),
+        }
+    }
+}
+
+/// The ANSI color code of a `term` color, covering the normal (30-37) and
+/// bright (90-97) ranges for the foreground and the normal (40-47) and bright
+/// (100-107) ranges for the background.
+fn ansi_code(base: u32, color: Color) -> u32 {
+    if color < 8 {
+        base + color
+    } else {
+        base + 60 + color - 8
+    }
+}
+
+/// Approximates a color of the 256 color palette by a named color.
+fn approximate(n: u8) -> Color {
+    match n {
+        0..=15 => Color::from(n),
+        16..=231 => {
+            let i = n - 16;
+            let (r, g, b) = (i / 36, (i / 6) % 6, i % 6);
+            let color = Color::from(r >= 3) * RED
+                + Color::from(g >= 3) * GREEN
+                + Color::from(b >= 3) * BLUE;
+            if r.max(g).max(b) >= 4 {
+                color + BRIGHT_BLACK
+            } else {
+                color
+            }
+        }
+        232..=237 => BLACK,
+        238..=243 => BRIGHT_BLACK,
+        244..=249 => WHITE,
+        _ => BRIGHT_WHITE,
+    }
+}
+
+#[cfg(feature = "truecolor")]
+/// Maps a 24-bit RGB color to the closest color of the 6x6x6 color cube of the
+/// 256 color palette.
+fn rgb_to_palette(r: u8, g: u8, b: u8) -> u8 {
+    let scale = |v: u8| ((u16::from(v) * 5 + 127) / 255) as u8;
+    16 + 36 * scale(r) + 6 * scale(g) + scale(b)
+}
+
+/// Retrieves the colors supported by the terminal via `COLORTERM` and `TERM`.
+pub(crate) fn env_color_depth() -> ColorDepth {
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        ColorDepth::TrueColor
+    } else if !colorterm.is_empty() || env::var("TERM").is_ok_and(|t| t.contains("256color")) {
+        ColorDepth::Palette
+    } else {
+        ColorDepth::Named
+    }
+}
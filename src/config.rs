@@ -0,0 +1,131 @@
+//! The logger configuration in a single struct
+use error::Error;
+use log::LevelFilter;
+use std::{collections::BTreeMap, path::PathBuf};
+use {
+    ColorMode, Format, LevelPad, LevelStyle, LoggerBuilder, TimeZone, TimestampMode,
+    TimestampPrecision,
+};
+
+/// The configuration of a `Logger`, which covers the common options in a
+/// single struct. With the `serde` feature it can be stored in and loaded from
+/// configuration files, where missing fields take their default values.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// let config = mowl::LoggerConfig {
+///     level: log::LevelFilter::Info,
+///     color_mode: mowl::ColorMode::Never,
+///     ..Default::default()
+/// };
+/// mowl::init_with_config(config).unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct LoggerConfig {
+    /// The global level, like `"INFO"`
+    pub level: LevelFilter,
+    /// Whether the output is colored, like `"auto"`
+    pub color_mode: ColorMode,
+    /// The format of the records, like `"text"`
+    pub format: Format,
+    /// The kind of timestamp, like `"wall_clock"`
+    pub timestamp_mode: TimestampMode,
+    /// A custom strftime-like format for the timestamp
+    pub timestamp_format: Option<String>,
+    /// The precision of wall-clock timestamps, like `"date"`
+    pub timestamp_precision: TimestampPrecision,
+    /// The time zone of wall-clock timestamps, like `"utc"`
+    pub timezone: TimeZone,
+    /// The representation of the level, like `"full"`
+    pub level_style: LevelStyle,
+    /// The padding of full level names, like `"left"`
+    pub level_padding: LevelPad,
+    /// The levels of specific modules, like `{"hyper": "WARN"}`
+    pub module_filters: BTreeMap<String, LevelFilter>,
+    /// Show the id of the current thread
+    pub thread_id: bool,
+    /// Show the name of the current thread
+    pub thread_name: bool,
+    /// Show the source location of the record
+    pub location: bool,
+    /// A file to append the records to instead of stderr
+    pub file: Option<PathBuf>,
+}
+
+impl Default for LoggerConfig {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Trace,
+            color_mode: ColorMode::default(),
+            format: Format::default(),
+            timestamp_mode: TimestampMode::default(),
+            timestamp_format: None,
+            timestamp_precision: TimestampPrecision::default(),
+            timezone: TimeZone::default(),
+            level_style: LevelStyle::default(),
+            level_padding: LevelPad::default(),
+            module_filters: BTreeMap::new(),
+            thread_id: false,
+            thread_name: false,
+            location: false,
+            file: None,
+        }
+    }
+}
+
+impl From<LoggerConfig> for LoggerBuilder {
+    fn from(config: LoggerConfig) -> Self {
+        let mut builder = LoggerBuilder::new()
+            .level(config.level)
+            .color_mode(config.color_mode)
+            .format(config.format)
+            .timestamp_mode(config.timestamp_mode)
+            .timestamp_precision(config.timestamp_precision)
+            .timezone(config.timezone)
+            .level_format(config.level_style)
+            .level_padding(config.level_padding)
+            .thread_id(config.thread_id)
+            .thread_name(config.thread_name)
+            .location(config.location);
+        if let Some(ref format) = config.timestamp_format {
+            builder = builder.timestamp_format(format);
+        }
+        for (module, level) in &config.module_filters {
+            builder = builder.module_filter(module, *level);
+        }
+        if let Some(path) = config.file {
+            builder = builder.file(path);
+        }
+        builder
+    }
+}
+
+/// Creates a logger from the configuration and registers it as global logger.
+///
+/// # Errors
+///
+/// An error is returned if the configuration is invalid or if a logger has
+/// already been set.
+pub fn init_with_config(config: LoggerConfig) -> Result<(), Error> {
+    LoggerBuilder::from(config).init()
+}
+
+/// Creates a logger from the configuration and registers it as global logger,
+/// like `init_with_config`.
+///
+/// # Errors
+///
+/// An error is returned if the configuration is invalid or if a logger has
+/// already been set.
+pub fn apply_config(config: LoggerConfig) -> Result<(), Error> {
+    init_with_config(config)
+}
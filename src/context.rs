@@ -0,0 +1,77 @@
+//! Key-value pairs of the current thread, which are appended to every record
+//! logged from it, like the mapped diagnostic context (MDC) of Java loggers
+//!
+//! ```
+//! # #[macro_use] extern crate log;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! mowl::context::insert("request", "42");
+//! info!("Handling the request"); // [INFO] Handling the request request=42
+//! mowl::context::remove("request");
+//!
+//! mowl::context::scope("user", "alice", || {
+//!     info!("Logged in"); // [INFO] Logged in user=alice
+//! });
+//! # }
+//! ```
+use std::{cell::RefCell, collections::HashMap};
+
+thread_local! {
+    static CTX: RefCell<HashMap<String, String>> = RefCell::new(HashMap::new());
+}
+
+/// Associates the value with the key for all following records of the current
+/// thread, replacing a previous value.
+pub fn insert(key: &str, value: &str) {
+    CTX.with(|ctx| ctx.borrow_mut().insert(key.to_owned(), value.to_owned()));
+}
+
+/// Removes the key from the context of the current thread.
+pub fn remove(key: &str) {
+    CTX.with(|ctx| ctx.borrow_mut().remove(key));
+}
+
+/// Associates the value with the key while running `f` and restores the
+/// previous value afterwards, even if `f` panics.
+pub fn scope<F: FnOnce() -> R, R>(key: &str, value: &str, f: F) -> R {
+    /// Restores the previous value of the key when dropped.
+    struct Guard {
+        key: String,
+        previous: Option<String>,
+    }
+
+    impl Drop for Guard {
+        fn drop(&mut self) {
+            // The context is already gone if the thread is shutting down
+            let _ = CTX.try_with(|ctx| {
+                let mut ctx = ctx.borrow_mut();
+                match self.previous.take() {
+                    Some(previous) => ctx.insert(self.key.clone(), previous),
+                    None => ctx.remove(&self.key),
+                }
+            });
+        }
+    }
+
+    let previous = CTX.with(|ctx| ctx.borrow_mut().insert(key.to_owned(), value.to_owned()));
+    let _guard = Guard {
+        key: key.to_owned(),
+        previous,
+    };
+    f()
+}
+
+/// The key-value pairs of the current thread, sorted by their keys.
+pub(crate) fn entries() -> Vec<(String, String)> {
+    let mut entries = CTX
+        .try_with(|ctx| {
+            ctx.borrow()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
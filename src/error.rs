@@ -0,0 +1,113 @@
+//! Error handling of the logger
+use log::{Level, SetLoggerError};
+use std::{
+    fmt::{self, Display},
+    io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::Instant,
+};
+use term::color::Color;
+
+/// The error type of all fallible operations of the logger.
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A global logger has already been set
+    #[error("logger already initialized")]
+    SetLogger(#[from] SetLoggerError),
+
+    /// Writing the log output failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+
+    /// Controlling the terminal failed
+    #[error("terminal error: {0}")]
+    Term(#[from] term::Error),
+
+    /// The log file could not be opened
+    #[error("unable to open log file '{}': {source}", path.display())]
+    OpenFile {
+        /// The path of the log file
+        path: PathBuf,
+        /// The underlying error
+        source: io::Error,
+    },
+
+    /// The parent directory of the log file does not exist
+    #[error("parent directory '{}' of log file does not exist", .0.display())]
+    MissingDirectory(PathBuf),
+
+    /// The timestamp format string is invalid
+    #[error("invalid timestamp format '{format}': {reason}")]
+    TimestampFormat {
+        /// The provided format string
+        format: String,
+        /// The reason why the format has been rejected
+        reason: String,
+    },
+
+    /// The field order does not contain the message exactly once
+    #[error("the field order must contain the message exactly once, not {0} times")]
+    FieldOrder(usize),
+
+    /// The color is not supported by the terminal
+    #[error("color {0} is out of the supported range")]
+    ColorOutOfRange(Color),
+
+    #[cfg(feature = "regex")]
+    /// A message filter is not a valid regular expression
+    #[error("invalid message filter: {0}")]
+    Regex(#[from] regex::Error),
+
+    /// The log level is neither a level name nor a number from 0 to 5
+    #[error("invalid log level '{0}'")]
+    Level(String),
+
+    /// The logging directives could not be parsed
+    #[error(transparent)]
+    Directive(#[from] ParseError),
+}
+
+/// The error of parsing invalid logging directives.
+#[derive(Clone, Debug, Error, PartialEq)]
+#[error("invalid directive '{directive}': {reason}")]
+pub struct ParseError {
+    /// The rejected directive
+    pub directive: String,
+    /// The reason why the directive has been rejected
+    pub reason: String,
+}
+
+/// Reports the failure of logging a record with its level and module to
+/// stderr, rate limited like `report`.
+pub(crate) fn report_failure<E: Display>(level: Level, module: &str, error: E) {
+    report(format_args!(
+        "[mowl] failed to log [{}/{}]: {}",
+        level, module, error
+    ));
+}
+
+/// Prints a failure of the logger to stderr. The failures are printed at most
+/// once per second to avoid flooding stderr if an output is failing
+/// continuously.
+pub(crate) fn report(message: fmt::Arguments) {
+    static START: OnceLock<Instant> = OnceLock::new();
+    // The milliseconds since `START` of the last report plus one, or zero if
+    // nothing has been reported yet
+    static LAST_REPORT: AtomicU64 = AtomicU64::new(0);
+
+    let now = START.get_or_init(Instant::now).elapsed().as_millis() as u64 + 1;
+    let last = LAST_REPORT.load(Ordering::Relaxed);
+    if last != 0 && now - last < 1000 {
+        return;
+    }
+    if LAST_REPORT
+        .compare_exchange(last, now, Ordering::Relaxed, Ordering::Relaxed)
+        .is_ok()
+    {
+        eprintln!("{}", message);
+    }
+}
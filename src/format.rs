@@ -0,0 +1,27 @@
+//! Formatting helpers for the fields of text records
+use std::time::Duration;
+
+/// Formats an elapsed duration depending on its magnitude, like `0.042s`,
+/// `1m 3s` or `1h 2m 3s`. Durations of a minute and more are truncated to
+/// whole seconds.
+///
+/// ```
+/// # extern crate mowl;
+/// # use std::time::Duration;
+/// assert_eq!(mowl::format::format_elapsed(Duration::from_millis(42)), "0.042s");
+/// assert_eq!(mowl::format::format_elapsed(Duration::from_secs(63)), "1m 3s");
+/// assert_eq!(mowl::format::format_elapsed(Duration::from_secs(3723)), "1h 2m 3s");
+/// ```
+pub fn format_elapsed(d: Duration) -> String {
+    let seconds = d.as_secs();
+    match seconds {
+        0..=59 => format!("{}.{:03}s", seconds, d.subsec_millis()),
+        60..=3599 => format!("{}m {}s", seconds / 60, seconds % 60),
+        _ => format!(
+            "{}h {}m {}s",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    }
+}
@@ -0,0 +1,90 @@
+//! Pluggable formatting of log records
+use clock;
+use error::Error;
+use log::Record;
+use std::io::Write;
+use TimeZone;
+
+/// The formatting of a single log record into the output.
+///
+/// A formatter set by `LoggerBuilder::formatter` replaces the built-in
+/// `Format` of the logger. It receives the unmodified record, so the
+/// formatter is responsible for the whole layout including the trailing
+/// newline, while filtering, hooks and outputs are still handled by the
+/// logger.
+///
+/// With the `validate-utf8` feature, a record which is not valid UTF-8 is
+/// written by the `TextFormatter` instead, with the message
+/// `<invalid utf-8 in log message>` followed by a hex dump of its first 32
+/// bytes.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// # use std::io::Write;
+/// use log::Record;
+/// use mowl::{Error, LogFormatter, LoggerBuilder};
+///
+/// struct Plain;
+///
+/// impl LogFormatter for Plain {
+///     fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), Error> {
+///         writeln!(sink, "{}: {}", record.level(), record.args())?;
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() {
+/// let logger = LoggerBuilder::new().formatter(Box::new(Plain)).build();
+/// # assert!(logger.is_ok());
+/// # }
+/// ```
+pub trait LogFormatter: Send + Sync {
+    /// Writes the record into the sink.
+    ///
+    /// # Errors
+    ///
+    /// The returned error is reported like any other failure of the output.
+    fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), Error>;
+}
+
+/// A formatter for uncolored text records like
+/// `[2024-01-01T12:00:00+00:00] [module] [INFO] message`, with the UTC timestamp in
+/// RFC 3339 format.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TextFormatter;
+
+impl LogFormatter for TextFormatter {
+    fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), Error> {
+        writeln!(
+            sink,
+            "[{}] [{}] [{}] {}",
+            clock::timestamp(None, TimeZone::Utc),
+            record.module_path().unwrap_or("?"),
+            record.level(),
+            record.args()
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "json")]
+/// A formatter for single line JSON objects with the keys `timestamp`,
+/// `level`, `module` and `message`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonFormatter;
+
+#[cfg(feature = "json")]
+impl LogFormatter for JsonFormatter {
+    fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), Error> {
+        let object = json!({
+            "timestamp": clock::timestamp(None, TimeZone::Utc),
+            "level": record.level().as_str(),
+            "module": record.module_path().unwrap_or("?"),
+            "message": record.args().to_string(),
+        });
+        serde_json::to_writer(&mut *sink, &object).map_err(std::io::Error::from)?;
+        writeln!(sink)?;
+        Ok(())
+    }
+}
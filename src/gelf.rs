@@ -0,0 +1,57 @@
+//! Framing of GELF (Graylog Extended Log Format) messages
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The maximum size of a GELF UDP datagram.
+const MAX_CHUNK_SIZE: usize = 8192;
+
+/// The magic bytes and the size of the header of a chunk.
+const CHUNK_MAGIC: [u8; 2] = [0x1e, 0x0f];
+const CHUNK_HEADER_SIZE: usize = 12;
+
+/// The maximum number of chunks of a message, larger messages are dropped.
+const MAX_CHUNKS: usize = 128;
+
+/// Distinguishes the ids of messages created at the same time.
+static MESSAGE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Splits the payload into UDP datagrams. Payloads which fit into a single
+/// datagram are sent as they are, larger ones as chunks sharing a message id.
+pub(crate) fn chunks(payload: &[u8]) -> Vec<Vec<u8>> {
+    if payload.len() <= MAX_CHUNK_SIZE {
+        return vec![payload.to_vec()];
+    }
+    let parts = payload.chunks(MAX_CHUNK_SIZE - CHUNK_HEADER_SIZE);
+    let count = parts.len();
+    if count > MAX_CHUNKS {
+        return vec![];
+    }
+    let id = message_id();
+    parts
+        .enumerate()
+        .map(|(sequence, part)| {
+            let mut chunk = Vec::with_capacity(CHUNK_HEADER_SIZE + part.len());
+            chunk.extend_from_slice(&CHUNK_MAGIC);
+            chunk.extend_from_slice(&id);
+            chunk.push(sequence as u8);
+            chunk.push(count as u8);
+            chunk.extend_from_slice(part);
+            chunk
+        })
+        .collect()
+}
+
+/// A message id, which has to be unique for the chunks of a message.
+fn message_id() -> [u8; 8] {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    (nanos
+        ^ MESSAGE_COUNTER
+            .fetch_add(1, Ordering::Relaxed)
+            .rotate_left(32))
+    .to_be_bytes()
+}
@@ -0,0 +1,126 @@
+//! Logging to the systemd journal via its native protocol
+//!
+//! ```no_run
+//! # #[macro_use] extern crate log;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! mowl::journald::init(log::LevelFilter::Info).unwrap();
+//! info!("Sent to the journal");
+//! # }
+//! ```
+use error::{self, Error};
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{env, os::unix::net::UnixDatagram, path::Path};
+use syslog;
+
+/// The socket of the journal.
+const SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Installs a `JournaldLogger` as global logger.
+///
+/// # Errors
+///
+/// An error is returned if the journal socket cannot be connected or if a
+/// logger has already been set.
+pub fn init(level: LevelFilter) -> Result<(), Error> {
+    let logger = JournaldLogger::new(level)?;
+    ::set_boxed_logger(Box::new(logger), level)
+}
+
+/// A logger which sends each record as structured entry to the journal.
+///
+/// Every entry contains the `PRIORITY`, `MESSAGE`, `SYSLOG_IDENTIFIER` and,
+/// if available, `CODE_FILE` and `CODE_LINE` fields. Entries which exceed the
+/// maximum datagram size are not supported.
+#[derive(Debug)]
+pub struct JournaldLogger {
+    level: LevelFilter,
+    socket: UnixDatagram,
+    identifier: String,
+}
+
+impl JournaldLogger {
+    /// Creates a logger connected to `/run/systemd/journal/socket`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the socket cannot be connected.
+    pub fn new(level: LevelFilter) -> Result<Self, Error> {
+        Self::with_socket(SOCKET, level)
+    }
+
+    /// Creates a logger connected to a custom journal socket.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the socket cannot be connected.
+    pub fn with_socket<P: AsRef<Path>>(path: P, level: LevelFilter) -> Result<Self, Error> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        Ok(Self {
+            level,
+            socket,
+            identifier: identifier(),
+        })
+    }
+
+    fn send(&self, record: &Record) -> Result<(), Error> {
+        let mut entry = vec![];
+        add_field(
+            &mut entry,
+            "PRIORITY",
+            &syslog::severity(record.level()).to_string(),
+        );
+        add_field(&mut entry, "MESSAGE", &record.args().to_string());
+        add_field(&mut entry, "SYSLOG_IDENTIFIER", &self.identifier);
+        if let Some(file) = record.file() {
+            add_field(&mut entry, "CODE_FILE", file);
+        }
+        if let Some(line) = record.line() {
+            add_field(&mut entry, "CODE_LINE", &line.to_string());
+        }
+        self.socket.send(&entry)?;
+        Ok(())
+    }
+}
+
+impl Log for JournaldLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            if let Err(e) = self.send(record) {
+                error::report_failure(record.level(), record.module_path().unwrap_or("?"), e);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Appends a field to the entry. Values containing a newline are written in
+/// the binary form, prefixed by their length as little endian `u64`.
+fn add_field(entry: &mut Vec<u8>, key: &str, value: &str) {
+    entry.extend_from_slice(key.as_bytes());
+    if value.contains('\n') {
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
+
+/// The name of the running executable, which identifies the entries.
+fn identifier() -> String {
+    env::args_os()
+        .next()
+        .as_ref()
+        .and_then(|arg| Path::new(arg).file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "mowl".to_owned())
+}
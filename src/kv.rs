@@ -0,0 +1,39 @@
+//! Key-value pairs attached to log records
+use log::{
+    kv::{Error, Key, Value, VisitSource},
+    Record,
+};
+
+/// Collects the key-value pairs of a record in their original order.
+pub(crate) fn collect<'a>(record: &'a Record) -> Vec<(Key<'a>, Value<'a>)> {
+    struct Collect<'a>(Vec<(Key<'a>, Value<'a>)>);
+
+    impl<'a> VisitSource<'a> for Collect<'a> {
+        fn visit_pair(&mut self, key: Key<'a>, value: Value<'a>) -> Result<(), Error> {
+            self.0.push((key, value));
+            Ok(())
+        }
+    }
+
+    let mut collect = Collect(vec![]);
+    // The visitor never fails, so errors can only stem from the source itself
+    let _ = record.key_values().visit(&mut collect);
+    collect.0
+}
+
+#[cfg(feature = "json")]
+/// Converts a value into its JSON representation, keeping numbers and booleans
+/// typed.
+pub(crate) fn to_json(value: &Value) -> ::serde_json::Value {
+    if let Some(b) = value.to_bool() {
+        b.into()
+    } else if let Some(n) = value.to_u64() {
+        n.into()
+    } else if let Some(n) = value.to_i64() {
+        n.into()
+    } else if let Some(n) = value.to_f64() {
+        n.into()
+    } else {
+        value.to_string().into()
+    }
+}
@@ -9,42 +9,280 @@
 //! warn!("Warning");
 //! # }
 //! ```
+//!
+//! Log calls above a level can be removed at compile time by the
+//! `max_level_*` and `release_max_level_*` features of the `log` crate, for
+//! example `log = { version = "0.4", features = ["release_max_level_info"] }`.
+//! The resulting level is available as `mowl::STATIC_MAX_LEVEL` and records
+//! above it are rejected by the logger right away.
+//!
+//! The `noop` feature removes all logging overhead without changing the call
+//! sites, for example in benchmark harnesses: every init function installs a
+//! `NoopLogger` and `Logger::log_result` returns right away. It cannot be
+//! combined with the `json`, `cbor` and `gelf` features.
 #![deny(missing_docs)]
-extern crate failure;
+// The formatting is still compiled with the noop feature, but never used
+#![cfg_attr(feature = "noop", allow(dead_code))]
+#[cfg(all(
+    feature = "noop",
+    any(feature = "json", feature = "cbor", feature = "gelf")
+))]
+compile_error!("the noop feature cannot be combined with the json, cbor or gelf features");
+
+#[cfg(feature = "fast-format")]
+extern crate arrayvec;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "cbor")]
+extern crate ciborium;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 extern crate log;
+#[cfg(feature = "regex")]
+extern crate regex;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(unix, feature = "unix"))]
+extern crate signal_hook;
+#[cfg(feature = "slog")]
+extern crate slog;
+#[cfg(any(feature = "json", feature = "gelf"))]
+#[macro_use]
+extern crate serde_json;
 extern crate term;
+#[macro_use]
+extern crate thiserror;
+#[cfg(feature = "time-compat")]
 extern crate time;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(all(windows, feature = "windows-event-log"))]
+extern crate windows_sys;
 
-use failure::Error;
+use async_logger::{Dispatcher, OverflowPolicy};
+use color::ColorDepth;
 use log::{Level, LevelFilter, Log, Metadata, Record};
-use std::io::prelude::*;
-use term::{color::*, StderrTerminal};
-use time::{Format, OffsetDateTime};
+use rate_limit::{Decision, RateLimiter};
+#[cfg(feature = "regex")]
+use regex::Regex;
+#[cfg(feature = "gelf")]
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::{
+    env,
+    ffi::OsStr,
+    fmt::{self, Display},
+    io::{self, prelude::*},
+    iter,
+    net::{SocketAddr, ToSocketAddrs},
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock, PoisonError,
+    },
+    time::Instant,
+};
+use term::{color::*, Attr};
+
+#[cfg(all(target_os = "android", feature = "android"))]
+pub use android::{init_android, AndroidLogger};
+pub use chained::{init_chained, ChainedLogger};
+pub use color::ColorSpec;
+pub use config::{apply_config, init_with_config, LoggerConfig};
+pub use error::{Error, ParseError};
+#[cfg(feature = "json")]
+pub use formatter::JsonFormatter;
+pub use formatter::{LogFormatter, TextFormatter};
+pub use log::STATIC_MAX_LEVEL;
+#[doc(hidden)]
+pub use macros::__private;
+pub use metrics::Metrics;
+pub use panic_hook::install_panic_hook;
+pub use sink::{BufferMode, LogSink, Output, SharedWriter};
+#[cfg(all(unix, feature = "unix"))]
+pub use syslog::SyslogFacility;
+#[cfg(all(windows, feature = "windows-event-log"))]
+pub use windows_event::{init_windows_event, WindowsEventLogger};
+
+#[cfg(all(target_os = "android", feature = "android"))]
+mod android;
+pub mod async_logger;
+mod chained;
+mod clock;
+mod color;
+mod config;
+pub mod context;
+mod error;
+pub mod format;
+pub mod formatter;
+mod gelf;
+#[cfg(all(unix, feature = "journald"))]
+pub mod journald;
+#[cfg(feature = "kv")]
+mod kv;
+mod macros;
+mod metrics;
+mod panic_hook;
+mod rate_limit;
+pub mod ring_buffer;
+mod rotate;
+mod sink;
+#[cfg(feature = "slog")]
+pub mod slog_compat;
+#[cfg(any(
+    all(unix, any(feature = "unix", feature = "journald")),
+    feature = "gelf"
+))]
+mod syslog;
+pub mod testing;
+#[cfg(feature = "tracing")]
+pub mod tracing_compat;
+#[cfg(all(unix, feature = "unix"))]
+pub mod unix;
+#[cfg(all(windows, feature = "windows-event-log"))]
+mod windows_event;
+
+/// The default colors of the log levels, indexed by `Level as usize - 1`.
+const DEFAULT_LEVEL_COLORS: [ColorSpec; 5] = [
+    ColorSpec::Named(BRIGHT_RED),
+    ColorSpec::Named(BRIGHT_YELLOW),
+    ColorSpec::Named(BRIGHT_GREEN),
+    ColorSpec::Named(BRIGHT_CYAN),
+    ColorSpec::Named(BRIGHT_WHITE),
+];
 
 /// Initializes the global logger with a specific `max_log_level`.
 ///
+/// The returned value holds the `usize` representation of the current level
+/// and can be used to change it at runtime. Note that records above the
+/// maximum level of the `log` crate are discarded before they reach the
+/// logger, so `log::set_max_level` has to be raised as well when increasing
+/// the verbosity.
+///
 /// ```
 /// # #[macro_use] extern crate log;
 /// # extern crate mowl;
 /// #
 /// # fn main() {
-/// mowl::init_with_level(log::LevelFilter::Warn).unwrap();
+/// use std::sync::atomic::Ordering;
+///
+/// let level = mowl::init_with_level(log::LevelFilter::Warn).unwrap();
 ///
 /// warn!("A warning");
 /// info!("A info message");
+///
+/// level.store(log::LevelFilter::Info as usize, Ordering::Relaxed);
+/// log::set_max_level(log::LevelFilter::Info);
+/// info!("Another info message");
 /// # }
 ///```
 ///
 /// # Errors
 ///
 /// An error is returned if a logger has already been set.
-pub fn init_with_level(log_level: LevelFilter) -> Result<(), Error> {
-    log::set_boxed_logger(Box::new(Logger {
-        level: log_level,
-        enable_colors: true,
-    }))
-    .map(|()| log::set_max_level(log_level))?;
-    Ok(())
+pub fn init_with_level(log_level: LevelFilter) -> Result<Arc<AtomicUsize>, Error> {
+    let logger = LoggerBuilder::new().level(log_level).build()?;
+    let level = logger.inner.level.clone();
+    set_logger(logger)?;
+    Ok(level)
+}
+
+/// Enables or disables the colors of the global logger at runtime, for example
+/// when an application detaches from its terminal. Enabling colors only has an
+/// effect on outputs which support them. Does nothing if the global logger has
+/// not been registered by mowl.
+pub fn set_colors(enabled: bool) {
+    if let Some(colors) = GLOBAL_COLORS.get() {
+        colors.store(enabled, Ordering::Relaxed);
+    }
+}
+
+/// Initializes the global logger to append to a file, which is rotated once it
+/// exceeds `max_bytes`. The rotated files are named `<path>.1`, `<path>.2` and
+/// so on, and at most `max_files` of them are kept.
+///
+/// # Errors
+///
+/// An error is returned if the file cannot be opened or if a logger has
+/// already been set.
+pub fn init_rotating(
+    path: &Path,
+    max_bytes: u64,
+    max_files: usize,
+    log_level: LevelFilter,
+) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::RotatingFile {
+            path: path.to_path_buf(),
+            max_bytes,
+            max_files,
+        })
+        .init()
+}
+
+/// Initializes the global logger to append to a file, which is renamed to
+/// `<path>.YYYY-MM-DD` once the UTC date changes. At most `max_days` of these
+/// historical files are kept.
+///
+/// # Errors
+///
+/// An error is returned if the file cannot be opened or if a logger has
+/// already been set.
+pub fn init_timed_rotating(
+    path: &Path,
+    max_days: usize,
+    log_level: LevelFilter,
+) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::TimedRotatingFile {
+            path: path.to_path_buf(),
+            max_days,
+        })
+        .init()
+}
+
+/// Initializes the global logger with the level of a verbosity count, like
+/// the number of `-v` flags of a command line tool, see `Verbosity`.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+pub fn init_with_verbosity(count: u8) -> Result<(), Error> {
+    init_with_config(LoggerConfig {
+        level: LevelFilter::from_verbosity(count),
+        ..Default::default()
+    })
+}
+
+/// Conversion of a verbosity count to a level.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// use mowl::Verbosity;
+///
+/// assert_eq!(log::LevelFilter::from_verbosity(2), log::LevelFilter::Info);
+/// # }
+/// ```
+pub trait Verbosity {
+    /// Maps 0 to `Error`, 1 to `Warn`, 2 to `Info`, 3 to `Debug` and
+    /// everything above to `Trace`.
+    fn from_verbosity(count: u8) -> Self;
+}
+
+impl Verbosity for LevelFilter {
+    fn from_verbosity(count: u8) -> Self {
+        match count {
+            0 => LevelFilter::Error,
+            1 => LevelFilter::Warn,
+            2 => LevelFilter::Info,
+            3 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
 }
 
 /// Initializes the global logger with a specific `max_log_level` and
@@ -66,14 +304,186 @@ pub fn init_with_level(log_level: LevelFilter) -> Result<(), Error> {
 ///
 /// An error is returned if a logger has already been set.
 pub fn init_with_level_and_without_colors(log_level: LevelFilter) -> Result<(), Error> {
-    log::set_boxed_logger(Box::new(Logger {
+    init_with_config(LoggerConfig {
+        level: log_level,
+        color_mode: ColorMode::Never,
+        ..Default::default()
+    })
+}
+
+/// Initializes the global logger with a specific `max_log_level` which writes
+/// into the provided file. The file is created if not existing and opened in
+/// append mode otherwise. Coloring is disabled for the file output.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// # let path = std::env::temp_dir().join("mowl-doc-init-with-file.log");
+/// mowl::init_with_file(&path, log::LevelFilter::Info).unwrap();
+///
+/// info!("A info message");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if the file cannot be opened or if a logger has
+/// already been set.
+pub fn init_with_file(path: &Path, log_level: LevelFilter) -> Result<(), Error> {
+    init_with_config(LoggerConfig {
         level: log_level,
-        enable_colors: false,
-    }))
-    .map(|()| log::set_max_level(log_level))?;
+        file: Some(path.to_path_buf()),
+        ..Default::default()
+    })
+}
+
+#[cfg(all(unix, feature = "unix"))]
+/// Initializes the global logger to send the records to the local syslog
+/// daemon via `/dev/log` or `/var/run/syslog`.
+///
+/// # Errors
+///
+/// An error is returned if the syslog socket cannot be connected or if a
+/// logger has already been set.
+pub fn init_to_syslog(facility: SyslogFacility, log_level: LevelFilter) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::Syslog {
+            facility,
+            socket: None,
+        })
+        .color_mode(ColorMode::Never)
+        .timestamp(false)
+        .init()
+}
+
+/// Initializes the global logger to send each record as UDP datagram to the
+/// address, like `"127.0.0.1:5140"`. UDP is unreliable, so records may get
+/// lost without notice, which makes this suitable for development and
+/// debugging only.
+///
+/// # Errors
+///
+/// An error is returned if the address cannot be resolved, the socket cannot
+/// be bound or if a logger has already been set.
+pub fn init_udp(addr: &str, log_level: LevelFilter) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::Udp(resolve(addr)?))
+        .color_mode(ColorMode::Never)
+        .init()
+}
+
+/// Initializes the global logger to send the records as lines via a TCP
+/// connection to the address, like `"127.0.0.1:5140"`. The connection is
+/// established on the first record and re-established if it breaks. While the
+/// address is unreachable, records are dropped with a warning on stderr, and
+/// reconnecting is retried with an exponential backoff.
+///
+/// # Errors
+///
+/// An error is returned if the address cannot be resolved or if a logger has
+/// already been set.
+pub fn init_tcp(addr: &str, log_level: LevelFilter) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::Tcp(resolve(addr)?))
+        .color_mode(ColorMode::Never)
+        .init()
+}
+
+#[cfg(all(unix, feature = "unix"))]
+/// Initializes the global logger to write to the named pipe (FIFO) at the
+/// path. Opening the pipe blocks until a reader opened it as well, so this
+/// function may block if no reader is ready. If the reader goes away, the
+/// records are written to stderr instead.
+///
+/// # Errors
+///
+/// An error is returned if the pipe cannot be opened or if a logger has
+/// already been set.
+pub fn init_to_fifo(path: &Path, log_level: LevelFilter) -> Result<(), Error> {
+    LoggerBuilder::new()
+        .level(log_level)
+        .output(Output::NamedPipe(path.to_path_buf()))
+        .color_mode(ColorMode::Never)
+        .init()
+}
+
+/// Creates a `Logger` writing to stderr without registering it as global
+/// logger. This avoids failing to set the global logger more than once in test
+/// suites, which run multiple tests in the same process.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// let logger = mowl::init_testing(log::LevelFilter::Info);
+/// let record = log::Record::builder()
+///     .args(format_args!("Tested"))
+///     .level(log::Level::Info)
+///     .build();
+/// logger.log_result(&record).unwrap();
+/// # }
+/// ```
+pub fn init_testing(log_level: LevelFilter) -> Logger {
+    LoggerBuilder::new()
+        .level(log_level)
+        .build()
+        .expect("the default logger configuration is valid")
+}
+
+/// Installs a `NoopLogger` as global logger, which discards all records. This
+/// is useful to silence the output in tests or benchmarks.
+///
+/// # Errors
+///
+/// An error is returned if a logger has already been set.
+pub fn init_noop() -> Result<(), Error> {
+    static NOOP: NoopLogger = NoopLogger;
+    log::set_logger(&NOOP)?;
+    log::set_max_level(LevelFilter::Off);
     Ok(())
 }
 
+/// A logger which discards all records.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopLogger;
+
+impl Log for NoopLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        false
+    }
+
+    fn log(&self, _: &Record) {}
+
+    fn flush(&self) {}
+}
+
+/// Initializes the global logger like `init_with_level`, but keeps an already
+/// set logger in place instead of failing. This is useful in test suites where
+/// multiple tests initialize the logger.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::init_or_default(log::LevelFilter::Info);
+/// mowl::init_or_default(log::LevelFilter::Debug);
+///
+/// info!("A info message");
+/// # }
+/// ```
+pub fn init_or_default(log_level: LevelFilter) {
+    // The default configuration is always valid, so the only possible error is
+    // an already set logger
+    let _ = init_with_level(log_level);
+}
+
 /// Initializes the global logger with `max_log_level` set to
 /// `LevelFilter::Trace`.
 ///
@@ -92,136 +502,2144 @@ pub fn init_with_level_and_without_colors(log_level: LevelFilter) -> Result<(),
 ///
 /// An error is returned if logger has already been initialized.
 pub fn init() -> Result<(), Error> {
-    init_with_level(LevelFilter::Trace)
+    init_with_config(LoggerConfig::default())
 }
 
-/// The logging structure
-pub struct Logger {
-    level: LevelFilter,
-    enable_colors: bool,
+/// Initializes the global logger from the `MOWL_LOG` environment variable,
+/// falling back to `RUST_LOG`. The variable contains a comma separated list of
+/// `env_logger` like directives, for example `warn,myapp=debug`. The global
+/// level defaults to `LevelFilter::Info`.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::init_from_env().unwrap();
+///
+/// info!("A info message");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if the directives are invalid or if a logger has
+/// already been set.
+pub fn init_from_env() -> Result<(), Error> {
+    let directives = env::var("MOWL_LOG")
+        .or_else(|_| env::var("RUST_LOG"))
+        .unwrap_or_default();
+    init_with_directives(&directives)
 }
 
-impl Log for Logger {
-    fn enabled(&self, metadata: &Metadata) -> bool {
-        metadata.level() <= self.level
-    }
+/// Initializes the global logger from a comma separated list of `env_logger`
+/// like directives, for example `warn,myapp=debug,hyper=error`. The global
+/// level defaults to `LevelFilter::Info`.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::init_with_directives("warn,myapp=debug").unwrap();
+///
+/// warn!("A warning");
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if the directives are invalid or if a logger has
+/// already been set.
+pub fn init_with_directives(directives: &str) -> Result<(), Error> {
+    parse_directives(directives)?
+        .into_iter()
+        .fold(
+            LoggerBuilder::new().level(LevelFilter::Info),
+            |builder, (module, level)| match module {
+                Some(module) => builder.module_filter(&module, level),
+                None => builder.level(level),
+            },
+        )
+        .init()
+}
 
-    fn log(&self, record: &Record) {
-        if self.enabled(record.metadata()) {
-            if let Err(e) = self.log_result(record) {
-                println!("Logging failed: {}", e);
-            }
-        }
+/// Parses a comma separated list of `env_logger` like directives.
+///
+/// A directive is either a level (`warn`), a module (`myapp`, which enables
+/// all levels for it) or a module with a level (`myapp=debug`). Levels are
+/// case insensitive and empty directives are skipped. A regex filter after a
+/// slash (`warn/foo`) is not supported and ignored. Levels may also be given as
+/// numbers, see `parse_level`.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// use log::LevelFilter;
+///
+/// assert_eq!(
+///     mowl::parse_directives("warn,myapp=debug").unwrap(),
+///     vec![
+///         (None, LevelFilter::Warn),
+///         (Some("myapp".to_owned()), LevelFilter::Debug)
+///     ]
+/// );
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned for a directive with an empty module name or an
+/// invalid level, or if more than one slash is given.
+pub fn parse_directives(s: &str) -> Result<Vec<(Option<String>, LevelFilter)>, ParseError> {
+    let mut parts = s.split('/');
+    let s = parts.next().unwrap_or_default();
+    if parts.nth(1).is_some() {
+        return Err(ParseError {
+            directive: s.to_owned(),
+            reason: "more than one slash".to_owned(),
+        });
     }
+    s.split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(|d| {
+            let error = |reason: &str| ParseError {
+                directive: d.to_owned(),
+                reason: reason.to_owned(),
+            };
+            let mut parts = d.splitn(2, '=');
+            let name = parts.next().unwrap_or_default().trim();
+            if name.is_empty() {
+                return Err(error("empty module name"));
+            }
+            match parts.next().map(str::trim) {
+                Some(level) => parse_level(level)
+                    .map(|level| (Some(name.to_owned()), level))
+                    .map_err(|_| error("invalid level")),
+                None => Ok(match parse_level(name) {
+                    Ok(level) => (None, level),
+                    Err(_) => (Some(name.to_owned()), LevelFilter::Trace),
+                }),
+            }
+        })
+        .collect()
+}
 
-    fn flush(&self) {}
+/// Parses a case insensitive level name like `warn` or `WARN`, or a numeric
+/// level from `0` (`Off`) to `5` (`Trace`).
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// use log::LevelFilter;
+///
+/// assert_eq!(mowl::parse_level("Warn").unwrap(), LevelFilter::Warn);
+/// assert_eq!(mowl::parse_level("4").unwrap(), LevelFilter::Debug);
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if the string is no valid level.
+pub fn parse_level(s: &str) -> Result<LevelFilter, Error> {
+    let level = match s {
+        "0" => LevelFilter::Off,
+        "1" => LevelFilter::Error,
+        "2" => LevelFilter::Warn,
+        "3" => LevelFilter::Info,
+        "4" => LevelFilter::Debug,
+        "5" => LevelFilter::Trace,
+        _ => s
+            .to_ascii_lowercase()
+            .parse()
+            .map_err(|_| Error::Level(s.to_owned()))?,
+    };
+    Ok(level)
 }
 
-impl Logger {
-    fn log_result(&self, record: &Record) -> Result<(), Error> {
-        // We have to create a new terminal on each log because Send is not fulfilled
-        let mut t = LogSink::new();
-        if self.enable_colors {
-            t.fg(BRIGHT_BLACK)?;
-        }
-        write!(
-            t,
-            "[{}] ",
-            OffsetDateTime::now_utc().lazy_format(Format::Rfc3339)
-        )?;
-        if self.enable_colors {
-            t.fg(BRIGHT_BLUE)?;
-        }
-        write!(t, "[{}] ", record.module_path().unwrap_or("?"))?;
-        if self.enable_colors {
-            match record.level() {
-                Level::Error => t.fg(BRIGHT_RED)?,
-                Level::Warn => t.fg(BRIGHT_YELLOW)?,
-                Level::Info => t.fg(BRIGHT_GREEN)?,
-                Level::Debug => t.fg(BRIGHT_CYAN)?,
-                Level::Trace => t.fg(BRIGHT_WHITE)?,
-            };
-        }
-        write!(t, "[{}] ", record.level())?;
-        if self.enable_colors {
-            t.reset()?;
+/// The format of the emitted log records.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Format {
+    /// Human readable text with bracketed fields, which is the default
+    #[default]
+    Text,
+    #[cfg(feature = "json")]
+    /// A single line JSON object per record without any coloring
+    Json,
+    /// Space separated `key=value` pairs per record without any coloring
+    Logfmt,
+    /// RFC 4180 comma separated values per record without any coloring,
+    /// preceded by a header line if the output is not a non-empty file
+    Csv,
+    #[cfg(feature = "cbor")]
+    /// A binary CBOR map per record with the keys of the JSON format, without
+    /// any coloring or delimiter
+    Cbor,
+    #[cfg(feature = "gelf")]
+    /// A GELF (Graylog Extended Log Format) JSON object per record, sent as
+    /// UDP chunks or null terminated via TCP to Graylog
+    Gelf {
+        /// The name of the host sending the records
+        host: String,
+    },
+}
+
+/// The representation of the level in text records.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LevelStyle {
+    /// The full level name like `[ERROR]`, which is the default
+    #[default]
+    Full,
+    /// The first letter of the level like `[E]`
+    Short,
+    /// A unicode symbol like `✖`, which falls back to `Short` if coloring is
+    /// disabled and the locale does not indicate UTF-8 support
+    Symbol,
+}
+
+/// The padding of full level names in text records to the width of five
+/// characters, which aligns the following fields.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum LevelPad {
+    /// Align the name to the left like `[WARN ]`
+    Left,
+    /// Align the name to the right like `[ WARN]`
+    Right,
+    /// No padding like `[WARN]`, which is the default
+    #[default]
+    None,
+}
+
+/// The representation of the module path in text records.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ModuleDisplay {
+    /// The full module path like `[myapp::net::http]`, which is the default
+    #[default]
+    Full,
+    /// Only the last segment of the module path like `[http]`
+    Short,
+    /// No module field at all
+    None,
+    /// The full module path, but no module field for records of the given
+    /// crate root, which adds no information to the output of single module
+    /// crates
+    OmitRoot(String),
+}
+
+/// The source of the module field in text records.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TargetDisplay {
+    /// The module path of the record, which is the default
+    #[default]
+    Module,
+    /// The target of the record, like `my_target` of
+    /// `info!(target: "my_target", "msg")`
+    Target,
+    /// Both as `[target/module]`
+    Both,
+}
+
+/// Controls whether the output is colored.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum ColorMode {
+    /// Colorize the output if it is written to a terminal, or to stderr or
+    /// stdout while `MOWL_FORCE_COLOR` is set, and colors are not disabled via
+    /// `NO_COLOR` or `TERM=dumb`, which is the default
+    #[default]
+    Auto,
+    /// Always colorize the output, even if it is written to a pipe or file
+    Always,
+    /// Never colorize the output
+    Never,
+}
+
+/// The kind of timestamp shown for each record.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampMode {
+    /// The current wall-clock time, formatted as RFC 3339 or with the custom
+    /// timestamp format, which is the default
+    #[default]
+    WallClock,
+    /// The monotonic time since the logger was initialized, like `[+0.042s]`,
+    /// `[+1m 3s]` or `[+1h 2m 3s]`
+    Elapsed,
+    /// No timestamp at all
+    Off,
+}
+
+/// The time zone of wall-clock timestamps.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimeZone {
+    /// The local time zone, which requires the `chrono` or `time-compat`
+    /// feature and falls back to UTC if the offset cannot be determined
+    Local,
+    /// Coordinated Universal Time, which is the default
+    #[default]
+    Utc,
+}
+
+/// The precision of wall-clock timestamps without a custom format.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum TimestampPrecision {
+    /// Only the date, like `2024-06-15`
+    Date,
+    /// The date and time in seconds, like `2024-06-15 14:30:00`
+    DateTime,
+    /// The full RFC 3339 timestamp, which is the default
+    #[default]
+    Full,
+    /// RFC 3339 in UTC with microseconds, like `2024-06-15T14:30:00.123456Z`
+    Microseconds,
+    /// RFC 3339 in UTC with nanoseconds, like
+    /// `2024-06-15T14:30:00.123456789Z`
+    Nanoseconds,
+}
+
+impl TimestampPrecision {
+    /// The format string of the precision, if it differs from RFC 3339.
+    fn format(self) -> Option<&'static str> {
+        match self {
+            TimestampPrecision::Date => Some("%F"),
+            TimestampPrecision::DateTime => Some("%F %T"),
+            TimestampPrecision::Full
+            | TimestampPrecision::Microseconds
+            | TimestampPrecision::Nanoseconds => None,
         }
-        writeln!(t, "{}", record.args())?;
-        Ok(())
     }
+}
 
-    /// Disable coloring output
-    pub fn disable_colors(&mut self) {
-        self.enable_colors = false;
+/// A field of text records.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub enum Field {
+    /// The timestamp, if it is not turned off
+    Timestamp,
+    /// The module and target according to the `TargetDisplay`
+    Module,
+    /// The level of the record
+    Level,
+    /// The process id, if enabled via `show_pid`
+    Pid,
+    /// The thread id and name, if enabled via `thread_id` or `thread_name`
+    ThreadId,
+    /// The application name, if one is set
+    AppName,
+    /// The message followed by its key-value pairs and location
+    Message,
+}
+
+/// The order in which the fields of text records are written. Fields which are
+/// not part of the order are omitted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldOrder(pub Vec<Field>);
+
+impl Default for FieldOrder {
+    fn default() -> Self {
+        FieldOrder(vec![
+            Field::Pid,
+            Field::Timestamp,
+            Field::AppName,
+            Field::Module,
+            Field::ThreadId,
+            Field::Level,
+            Field::Message,
+        ])
     }
 }
 
-/// Different output implementations for the logger.
-enum LogSink {
-    /// Stderr Terminal as default
-    Terminal(Box<StderrTerminal>),
-    /// Stderr as fallback if a terminal cannot be instantiated
-    Fallback(std::io::Stderr),
+/// A builder to configure and create a `Logger`.
+///
+/// ```
+/// # #[macro_use] extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::LoggerBuilder::new()
+///     .level(log::LevelFilter::Info)
+///     .colors(false)
+///     .init()
+///     .unwrap();
+///
+/// info!("A info message");
+/// # }
+/// ```
+pub struct LoggerBuilder {
+    level: LevelFilter,
+    color_mode: ColorMode,
+    outputs: Vec<Output>,
+    buffer_mode: BufferMode,
+    #[cfg(feature = "gzip")]
+    compress: bool,
+    async_dispatch: Option<(usize, OverflowPolicy)>,
+    format: Format,
+    timestamp_format: Option<String>,
+    timestamp_precision: TimestampPrecision,
+    timezone: TimeZone,
+    level_aliases: [Level; 5],
+    level_colors: [ColorSpec; 5],
+    level_bg_colors: [Option<ColorSpec>; 5],
+    level_outputs: [Option<Output>; 5],
+    module_filters: Vec<(String, LevelFilter)>,
+    #[cfg(feature = "regex")]
+    filters_in: Vec<String>,
+    #[cfg(feature = "regex")]
+    filters_out: Vec<String>,
+    rate_limits: [Option<u32>; 5],
+    pre_hooks: Vec<PreHook>,
+    error_callbacks: Vec<ErrorCallback>,
+    suppress_duplicates: bool,
+    app_name: Option<String>,
+    show_pid: bool,
+    show_thread_id: bool,
+    show_thread_name: bool,
+    show_location: bool,
+    multiline_indent: bool,
+    max_message_length: Option<usize>,
+    truncation_suffix: String,
+    bold_level: bool,
+    underline_errors: bool,
+    timestamp_mode: TimestampMode,
+    level_style: LevelStyle,
+    level_padding: LevelPad,
+    module_display: ModuleDisplay,
+    target_display: TargetDisplay,
+    field_open: String,
+    field_close: String,
+    field_separator: String,
+    field_order: FieldOrder,
+    formatter: Option<Box<dyn LogFormatter>>,
 }
 
-impl LogSink {
-    fn new() -> Self {
-        if let Some(term) = term::stderr() {
-            Self::Terminal(term)
-        } else {
-            Self::Fallback(std::io::stderr())
+impl Default for LoggerBuilder {
+    fn default() -> Self {
+        Self {
+            level: LevelFilter::Trace,
+            color_mode: ColorMode::default(),
+            outputs: vec![Output::default()],
+            buffer_mode: BufferMode::default(),
+            #[cfg(feature = "gzip")]
+            compress: false,
+            async_dispatch: None,
+            format: Format::default(),
+            timestamp_format: None,
+            timestamp_precision: TimestampPrecision::default(),
+            timezone: TimeZone::default(),
+            level_aliases: [
+                Level::Error,
+                Level::Warn,
+                Level::Info,
+                Level::Debug,
+                Level::Trace,
+            ],
+            level_colors: DEFAULT_LEVEL_COLORS,
+            level_bg_colors: [None; 5],
+            level_outputs: Default::default(),
+            module_filters: vec![],
+            #[cfg(feature = "regex")]
+            filters_in: vec![],
+            #[cfg(feature = "regex")]
+            filters_out: vec![],
+            rate_limits: [None; 5],
+            pre_hooks: vec![],
+            error_callbacks: vec![],
+            suppress_duplicates: false,
+            app_name: None,
+            show_pid: false,
+            show_thread_id: false,
+            show_thread_name: false,
+            show_location: false,
+            multiline_indent: false,
+            max_message_length: None,
+            truncation_suffix: "…".to_owned(),
+            bold_level: true,
+            underline_errors: true,
+            timestamp_mode: TimestampMode::default(),
+            level_style: LevelStyle::default(),
+            level_padding: LevelPad::default(),
+            module_display: ModuleDisplay::default(),
+            target_display: TargetDisplay::default(),
+            field_open: "[".to_owned(),
+            field_close: "]".to_owned(),
+            field_separator: " ".to_owned(),
+            field_order: FieldOrder::default(),
+            formatter: None,
         }
     }
+}
+
+impl LoggerBuilder {
+    /// Creates a new builder with `LevelFilter::Trace` and coloring enabled.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-    fn fg(&mut self, color: Color) -> Result<(), Error> {
-        if let Self::Terminal(t) = self {
-            t.fg(color)?;
-        }
-        Ok(())
+    /// Sets the maximum log level.
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
     }
 
-    fn reset(&mut self) -> Result<(), Error> {
-        if let Self::Terminal(t) = self {
-            t.reset()?;
-        }
-        Ok(())
+    /// Enables or disables the coloring of the output, which is the same as
+    /// using `ColorMode::Auto` or `ColorMode::Never`.
+    pub fn colors(self, enable: bool) -> Self {
+        self.color_mode(if enable {
+            ColorMode::Auto
+        } else {
+            ColorMode::Never
+        })
     }
-}
 
-/// Implement Write for `LogSink` by forwarding to the underlying Writers
-impl std::io::Write for LogSink {
-    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        match self {
-            Self::Terminal(t) => t.write(buf),
-            Self::Fallback(e) => e.write(buf),
-        }
+    /// Sets the color mode, which is `ColorMode::Auto` per default.
+    pub fn color_mode(mut self, mode: ColorMode) -> Self {
+        self.color_mode = mode;
+        self
     }
 
-    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
-        match self {
-            Self::Terminal(t) => t.write_vectored(bufs),
-            Self::Fallback(e) => e.write_vectored(bufs),
-        }
+    /// Sets the output destination, which is stderr per default. This
+    /// replaces all previously set or added outputs.
+    pub fn output(mut self, output: Output) -> Self {
+        self.outputs = vec![output];
+        self
     }
 
-    fn flush(&mut self) -> std::io::Result<()> {
-        match self {
-            Self::Terminal(t) => t.flush(),
-            Self::Fallback(e) => e.flush(),
-        }
+    /// Adds an additional output destination, for example to log to stderr
+    /// and a file at the same time.
+    pub fn add_sink(mut self, output: Output) -> Self {
+        self.outputs.push(output);
+        self
     }
 
-    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
-        match self {
-            Self::Terminal(t) => t.write_all(buf),
-            Self::Fallback(e) => e.write_all(buf),
-        }
+    /// Writes the output into the provided file instead of stderr. The file is
+    /// opened in append mode and colors are not written into it.
+    pub fn file<P: AsRef<Path>>(self, path: P) -> Self {
+        self.output(Output::File(path.as_ref().to_path_buf()))
     }
 
-    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> std::io::Result<()> {
-        match self {
-            Self::Terminal(t) => t.write_fmt(args),
-            Self::Fallback(e) => e.write_fmt(args),
-        }
+    /// Sets how the output is buffered, which is `BufferMode::None` per
+    /// default. See `BufferMode` for the trade-off between throughput and
+    /// latency.
+    pub fn buffer_mode(mut self, mode: BufferMode) -> Self {
+        self.buffer_mode = mode;
+        self
+    }
+
+    #[cfg(feature = "gzip")]
+    /// Compresses the files rotated by an `Output::RotatingFile` into
+    /// `<path>.1.gz` and so on. The compression runs on a separate thread, so
+    /// logging does not block on it.
+    pub fn compress(mut self, compress: bool) -> Self {
+        self.compress = compress;
+        self
+    }
+
+    /// Writes the records from a background thread, which queues up to
+    /// `capacity` records and handles a full queue according to the `policy`.
+    /// The records are still rendered on the calling thread. Colors are written
+    /// as ANSI escape sequences and disabled for multiple outputs.
+    pub fn async_dispatch(mut self, capacity: usize, policy: OverflowPolicy) -> Self {
+        self.async_dispatch = Some((capacity, policy));
+        self
+    }
+
+    /// Sets the format of the emitted log records.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Shows the timestamp of each record, which is enabled per default. It can be
+    /// disabled if the log output is already timestamped, for example by the
+    /// systemd journal.
+    pub fn timestamp(mut self, enable: bool) -> Self {
+        self.timestamp_mode = if enable {
+            TimestampMode::WallClock
+        } else {
+            TimestampMode::Off
+        };
+        self
+    }
+
+    /// Sets the kind of timestamp, which is `TimestampMode::WallClock` per
+    /// default.
+    pub fn timestamp_mode(mut self, mode: TimestampMode) -> Self {
+        self.timestamp_mode = mode;
+        self
+    }
+
+    /// Sets a custom strftime-like format for the timestamp in UTC, for
+    /// example `"%H:%M:%S"`. RFC 3339 is used if not set. The supported
+    /// specifiers are the ones of `chrono` or `time` if the `chrono` or
+    /// `time-compat` feature is enabled, and otherwise
+    /// `%a %A %b %B %d %e %F %H %j %m %M %N %S %T %y %Y %z %%` as well as
+    /// `%.3f %.6f %.9f` for milliseconds, microseconds and nanoseconds, like
+    /// `"%Y-%m-%d %H:%M:%S%.3f"`.
+    pub fn timestamp_format(mut self, format: &str) -> Self {
+        self.timestamp_format = Some(format.to_owned());
+        self
     }
+
+    /// Sets the precision of wall-clock timestamps, which is
+    /// `TimestampPrecision::Full` per default. A custom timestamp format takes
+    /// precedence.
+    pub fn timestamp_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.timestamp_precision = precision;
+        self
+    }
+
+    /// Sets the time zone of wall-clock timestamps, which is `TimeZone::Utc`
+    /// per default. Timestamps with microseconds or nanoseconds are always in
+    /// UTC.
+    pub fn timezone(mut self, timezone: TimeZone) -> Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Displays the records of level `from` as level `to`, including its color.
+    /// The filtering still uses the original level.
+    ///
+    /// ```
+    /// # extern crate log;
+    /// # extern crate mowl;
+    /// #
+    /// # fn main() {
+    /// use log::Level;
+    ///
+    /// let mut builder = mowl::LoggerBuilder::new();
+    /// if !cfg!(debug_assertions) {
+    ///     builder = builder.level_alias(Level::Debug, Level::Info);
+    /// }
+    /// builder.init().unwrap();
+    /// # }
+    /// ```
+    pub fn level_alias(mut self, from: Level, to: Level) -> Self {
+        self.level_aliases[from as usize - 1] = to;
+        self
+    }
+
+    /// Overrides the color of a specific log level, for example
+    /// `.level_color(Level::Warn, term::color::MAGENTA)` or
+    /// `.level_color(Level::Info, ColorSpec::Color256(208))`.
+    pub fn level_color<C: Into<ColorSpec>>(mut self, level: Level, color: C) -> Self {
+        self.level_colors[level as usize - 1] = color.into();
+        self
+    }
+
+    /// Writes the records of a specific level to their own output instead of
+    /// the general outputs, for example `.level_target(Level::Info,
+    /// Output::Stdout)` to separate them from the errors on stderr. Colors are
+    /// detected separately for each output. These records are written
+    /// directly, even if `async_dispatch` is enabled.
+    pub fn level_target(mut self, level: Level, output: Output) -> Self {
+        self.level_outputs[level as usize - 1] = Some(output);
+        self
+    }
+
+    /// Sets the background color of the level token of a specific log level,
+    /// for example `.level_bg_color(Level::Error, term::color::RED)`.
+    pub fn level_bg_color<C: Into<ColorSpec>>(mut self, level: Level, color: C) -> Self {
+        self.level_bg_colors[level as usize - 1] = Some(color.into());
+        self
+    }
+
+    /// Overrides the maximum log level for a module and all of its sub
+    /// modules, for example `.module_filter("hyper", LevelFilter::Warn)`. The
+    /// longest matching module prefix wins.
+    pub fn module_filter(mut self, module: &str, level: LevelFilter) -> Self {
+        self.module_filters.retain(|(m, _)| m != module);
+        self.module_filters.push((module.to_owned(), level));
+        self
+    }
+
+    #[cfg(feature = "regex")]
+    /// Only logs records whose message matches the regular expression. If
+    /// called multiple times, a record has to match one of the patterns. The
+    /// pattern is validated when the logger is built.
+    pub fn filter_in(mut self, pattern: &str) -> Self {
+        self.filters_in.push(pattern.to_owned());
+        self
+    }
+
+    #[cfg(feature = "regex")]
+    /// Drops records whose message matches the regular expression, which is
+    /// checked after `filter_in`. If called multiple times, a record is
+    /// dropped if it matches any of the patterns. The pattern is validated
+    /// when the logger is built.
+    pub fn filter_out(mut self, pattern: &str) -> Self {
+        self.filters_out.push(pattern.to_owned());
+        self
+    }
+
+    /// Adds a hook which decides whether a record is logged, for example to
+    /// drop records containing secrets. If multiple hooks are added, a record
+    /// is only logged if all of them return `true`.
+    pub fn pre_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Record) -> bool + Send + Sync + 'static,
+    {
+        self.pre_hooks.push(Box::new(hook));
+        self
+    }
+
+    /// Adds a callback which is called with the message of every logged
+    /// `Error` record, for example to send an alert. The callback is called
+    /// after the record was written, even if writing failed.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        self.error_callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Limits the records of a level to at most `max_per_sec` per second. The
+    /// number of dropped records is reported by a single
+    /// `[N messages suppressed]` record with the first record of the next
+    /// window, even if that record is dropped as well, like with a
+    /// `max_per_sec` of zero.
+    pub fn rate_limit(mut self, level: Level, max_per_sec: u32) -> Self {
+        self.rate_limits[level as usize - 1] = Some(max_per_sec);
+        self
+    }
+
+    /// Prints consecutive records with the same level, module and message only
+    /// once, followed by a `[repeated N times]` record when a different record
+    /// is logged or the logger is flushed.
+    pub fn suppress_duplicates(mut self, enable: bool) -> Self {
+        self.suppress_duplicates = enable;
+        self
+    }
+
+    /// Shows the name of the application as `[name]` after the timestamp, which
+    /// distinguishes the records of multiple applications in the same log
+    /// aggregator.
+    pub fn app_name(mut self, name: &str) -> Self {
+        self.app_name = Some(name.to_owned());
+        self
+    }
+
+    /// Shows the id of the process as `[12345]` in front of all other fields.
+    pub fn show_pid(mut self, enable: bool) -> Self {
+        self.show_pid = enable;
+        self
+    }
+
+    /// Shows the id of the current thread as `[thread-N]` in each record.
+    pub fn thread_id(mut self, enable: bool) -> Self {
+        self.show_thread_id = enable;
+        self
+    }
+
+    /// Shows the name of the current thread as `[name]` in each record, or
+    /// `[<anonymous>]` for unnamed threads. Combined with the thread id it is
+    /// shown as `[name/N]`.
+    pub fn thread_name(mut self, enable: bool) -> Self {
+        self.show_thread_name = enable;
+        self
+    }
+
+    /// Appends the source location as `(src/main.rs:42)` to each record, if
+    /// available.
+    pub fn location(mut self, enable: bool) -> Self {
+        self.show_location = enable;
+        self
+    }
+
+    /// Indents the continuation lines of multi-line messages by the width of
+    /// the prefix fields, which keeps them aligned with the first line.
+    pub fn multiline_indent(mut self, enable: bool) -> Self {
+        self.multiline_indent = enable;
+        self
+    }
+
+    /// Truncates messages which are longer than `length` bytes at the previous
+    /// character boundary and appends the truncation suffix.
+    pub fn max_message_length(mut self, length: usize) -> Self {
+        self.max_message_length = Some(length);
+        self
+    }
+
+    /// Sets the suffix of truncated messages, which is `…` per default.
+    pub fn truncation_suffix(mut self, suffix: &str) -> Self {
+        self.truncation_suffix = suffix.to_owned();
+        self
+    }
+
+    /// Prints the level token in bold, which is enabled per default.
+    pub fn bold_level(mut self, enable: bool) -> Self {
+        self.bold_level = enable;
+        self
+    }
+
+    /// Underlines the messages of `Error` records, which is enabled per
+    /// default.
+    pub fn underline_errors(mut self, enable: bool) -> Self {
+        self.underline_errors = enable;
+        self
+    }
+
+    /// Sets the representation of the module path in text records.
+    pub fn module_display(mut self, display: ModuleDisplay) -> Self {
+        self.module_display = display;
+        self
+    }
+
+    /// Omits the module field of records from the crate root, see
+    /// `ModuleDisplay::OmitRoot`.
+    ///
+    /// ```
+    /// # extern crate mowl;
+    /// mowl::LoggerBuilder::new()
+    ///     .omit_root_module(env!("CARGO_CRATE_NAME"))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn omit_root_module(self, crate_name: &str) -> Self {
+        self.module_display(ModuleDisplay::OmitRoot(crate_name.to_owned()))
+    }
+
+    /// Sets whether the module field shows the module path, the target or
+    /// both of the record.
+    pub fn target_display(mut self, display: TargetDisplay) -> Self {
+        self.target_display = display;
+        self
+    }
+
+    /// Sets the representation of the level in text records.
+    pub fn level_format(mut self, style: LevelStyle) -> Self {
+        self.level_style = style;
+        self
+    }
+
+    /// Sets the padding of full level names in text records.
+    pub fn level_padding(mut self, padding: LevelPad) -> Self {
+        self.level_padding = padding;
+        self
+    }
+
+    /// Sets the characters which surround each field of text records, which
+    /// are `[` and `]` per default.
+    pub fn field_delimiter(mut self, open: char, close: char) -> Self {
+        self.field_open = open.to_string();
+        self.field_close = close.to_string();
+        self
+    }
+
+    /// Sets the separator between the fields of text records, which is a
+    /// single space per default.
+    pub fn field_separator(mut self, separator: &str) -> Self {
+        self.field_separator = separator.to_owned();
+        self
+    }
+
+    /// Sets the order of the fields of text records, which omits all fields
+    /// that are not part of it. The order has to contain `Field::Message`
+    /// exactly once, which is checked when the logger is built.
+    pub fn field_order(mut self, order: Vec<Field>) -> Self {
+        self.field_order = FieldOrder(order);
+        self
+    }
+
+    /// Sets a custom formatter for the records, which replaces the `Format` as
+    /// well as all options of text records. Colors are not applied to the
+    /// output of the formatter.
+    pub fn formatter(mut self, formatter: Box<dyn LogFormatter>) -> Self {
+        self.formatter = Some(formatter);
+        self
+    }
+
+    /// Creates the `Logger` without registering it as global logger.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the configuration is invalid or the log file
+    /// cannot be opened.
+    pub fn build(mut self) -> Result<Logger, Error> {
+        #[cfg(feature = "regex")]
+        let filters_in = self
+            .filters_in
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        #[cfg(feature = "regex")]
+        let filters_out = self
+            .filters_out
+            .iter()
+            .map(|p| Regex::new(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let messages = self
+            .field_order
+            .0
+            .iter()
+            .filter(|f| **f == Field::Message)
+            .count();
+        if messages != 1 {
+            return Err(Error::FieldOrder(messages));
+        }
+        if self.timestamp_format.is_none() {
+            self.timestamp_format = self.timestamp_precision.format().map(str::to_owned);
+        }
+        if let Some(ref format) = self.timestamp_format {
+            clock::validate_format(format).map_err(|reason| Error::TimestampFormat {
+                format: format.clone(),
+                reason,
+            })?;
+        }
+        for color in self
+            .level_colors
+            .iter()
+            .chain(self.level_bg_colors.iter().flatten())
+        {
+            match *color {
+                ColorSpec::Named(c) if c > BRIGHT_WHITE => return Err(Error::ColorOutOfRange(c)),
+                _ => {}
+            }
+        }
+        let force_colors = self.color_mode == ColorMode::Always;
+        let (sink, enable_colors) = self.open_sink(&self.outputs)?;
+        let enable_colors =
+            enable_colors && (self.async_dispatch.is_none() || self.outputs.len() == 1);
+        let mut level_sinks: [Option<(Arc<Mutex<LogSink>>, bool)>; 5] = Default::default();
+        for (index, output) in self.level_outputs.iter().enumerate() {
+            if let Some(ref output) = *output {
+                // Levels sharing the same output share its sink as well
+                let shared = self.level_outputs[..index]
+                    .iter()
+                    .position(|o| o.as_ref() == Some(output));
+                level_sinks[index] = match shared {
+                    Some(other) => level_sinks[other].clone(),
+                    None => {
+                        let (sink, colors) = self.open_sink(std::slice::from_ref(output))?;
+                        Some((Arc::new(Mutex::new(sink)), colors))
+                    }
+                };
+            }
+        }
+        let sink = Arc::new(Mutex::new(sink));
+        let dispatcher = match self.async_dispatch {
+            Some((capacity, policy)) => Some(Dispatcher::spawn(sink.clone(), capacity, policy)?),
+            None => None,
+        };
+        self.module_filters
+            .sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(Logger {
+            inner: Arc::new(Inner {
+                level: Arc::new(AtomicUsize::new(self.level as usize)),
+                enable_colors: AtomicBool::new(enable_colors),
+                colors: Arc::new(AtomicBool::new(true)),
+                force_colors,
+                format: self.format,
+                timestamp_format: self.timestamp_format,
+                timestamp_precision: self.timestamp_precision,
+                timezone: self.timezone,
+                level_aliases: self.level_aliases,
+                level_colors: self.level_colors,
+                level_bg_colors: self.level_bg_colors,
+                color_depth: color::env_color_depth(),
+                module_filters: self.module_filters,
+                #[cfg(feature = "regex")]
+                filters_in,
+                #[cfg(feature = "regex")]
+                filters_out,
+                rate_limiter: RateLimiter::new(self.rate_limits),
+                pre_hooks: self.pre_hooks,
+                error_callbacks: self.error_callbacks,
+                metrics: Arc::new(Metrics::default()),
+                suppress_duplicates: self.suppress_duplicates,
+                last_record: Mutex::new(None),
+                repeat_count: Mutex::new(0),
+                app_name: self.app_name,
+                show_pid: self.show_pid,
+                pid: std::process::id(),
+                show_thread_id: self.show_thread_id,
+                show_thread_name: self.show_thread_name,
+                show_location: self.show_location,
+                multiline_indent: self.multiline_indent,
+                max_message_length: self.max_message_length,
+                truncation_suffix: self.truncation_suffix,
+                bold_level: self.bold_level,
+                underline_errors: self.underline_errors,
+                timestamp_mode: self.timestamp_mode,
+                start: Instant::now(),
+                level_style: self.level_style,
+                utf8_locale: utf8_locale(),
+                level_padding: self.level_padding,
+                module_display: self.module_display,
+                target_display: self.target_display,
+                field_open: self.field_open,
+                field_close: self.field_close,
+                field_separator: self.field_separator,
+                field_order: self.field_order,
+                formatter: self.formatter,
+                sink,
+                level_sinks,
+                dispatcher,
+            }),
+        })
+    }
+
+    /// Creates the `Logger` and registers it as global logger.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the configuration is invalid or if a logger has
+    /// already been set.
+    pub fn init(self) -> Result<(), Error> {
+        #[cfg(feature = "noop")]
+        return init_noop();
+        #[cfg(not(feature = "noop"))]
+        set_logger(self.build()?)
+    }
+
+    /// Creates the sink for the outputs and writes the CSV header if needed.
+    /// Returns the sink together with whether it should be colored.
+    fn open_sink(&self, outputs: &[Output]) -> Result<(LogSink, bool), Error> {
+        // Appending to an existing CSV file must not repeat the header, which
+        // is therefore decided per output
+        let csv_header = match (&self.format, self.timestamp_mode) {
+            (Format::Csv, TimestampMode::Off) => Some("level,module,message\n"),
+            (Format::Csv, _) => Some("timestamp,level,module,message\n"),
+            _ => None,
+        };
+        let force_colors = self.color_mode == ColorMode::Always;
+        // The sink is only adjusted below for some features
+        #[cfg_attr(not(any(feature = "gelf", feature = "gzip")), allow(unused_mut))]
+        let mut sink = LogSink::new(outputs, force_colors, self.buffer_mode, csv_header)?;
+        #[cfg(feature = "gelf")]
+        if let Format::Gelf { .. } = self.format {
+            sink.enable_gelf();
+        }
+        #[cfg(feature = "gzip")]
+        if self.compress {
+            sink.enable_compression();
+        }
+        let enable_colors = match self.color_mode {
+            ColorMode::Auto => {
+                sink.is_terminal() || (sink::env_forces_colors() && sink.is_std_stream())
+            }
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+        };
+        Ok((sink, enable_colors))
+    }
+}
+
+/// The level of the global logger and the most verbose level of its module
+/// filters, which allow changing the level after the registration.
+pub(crate) static GLOBAL_LEVEL: OnceLock<(Arc<AtomicUsize>, LevelFilter)> = OnceLock::new();
+
+/// Whether the global logger colors its output.
+static GLOBAL_COLORS: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+#[cfg(all(unix, feature = "unix"))]
+/// A clone of the global logger, which allows writing records regardless of
+/// its level.
+pub(crate) static GLOBAL_LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Registers the logger as global logger, or a `NoopLogger` with the `noop`
+/// feature.
+pub(crate) fn set_boxed_logger(logger: Box<dyn Log>, level: LevelFilter) -> Result<(), Error> {
+    if cfg!(feature = "noop") {
+        return init_noop();
+    }
+    log::set_boxed_logger(logger).map(|()| log::set_max_level(level))?;
+    Ok(())
+}
+
+#[cfg(feature = "noop")]
+/// Registers a `NoopLogger` instead of the logger.
+fn set_logger(_: Logger) -> Result<(), Error> {
+    init_noop()
+}
+
+#[cfg(not(feature = "noop"))]
+/// Registers the logger as global logger.
+fn set_logger(logger: Logger) -> Result<(), Error> {
+    let level = logger.max_level();
+    let global_level = logger.inner.level.clone();
+    let filters_level = logger.inner.module_filters.iter().map(|&(_, l)| l).max();
+    let colors = logger.inner.colors.clone();
+    #[cfg(all(unix, feature = "unix"))]
+    let clone = logger.clone();
+    set_boxed_logger(Box::new(logger), level)?;
+    #[cfg(all(unix, feature = "unix"))]
+    let _ = GLOBAL_LOGGER.set(clone);
+    let _ = GLOBAL_LEVEL.set((global_level, filters_level.unwrap_or(LevelFilter::Off)));
+    let _ = GLOBAL_COLORS.set(colors);
+    Ok(())
+}
+
+/// A hook deciding whether a record is logged.
+type PreHook = Box<dyn Fn(&Record) -> bool + Send + Sync>;
+
+/// A callback notified about logged errors.
+type ErrorCallback = Box<dyn Fn(&str) + Send + Sync>;
+
+/// The logging structure
+///
+/// The output is guarded by a mutex, which is held while a whole record is
+/// written. Records logged from multiple threads at the same time are therefore
+/// never interleaved within a line.
+///
+/// Records are written on the calling thread, which is blocked by a slow
+/// output. Use `LoggerBuilder::async_dispatch` or `BufferMode::Block` to
+/// decouple the caller from the output.
+///
+/// Cloning the logger is cheap, since all clones share the same outputs,
+/// level, metrics and state, which allows to use one logger from multiple
+/// threads without wrapping it in an `Arc`.
+#[derive(Clone)]
+pub struct Logger {
+    inner: Arc<Inner>,
+}
+
+/// The configuration and state of a `Logger`, which is shared by its clones.
+struct Inner {
+    level: Arc<AtomicUsize>,
+    enable_colors: AtomicBool,
+    colors: Arc<AtomicBool>,
+    force_colors: bool,
+    format: Format,
+    timestamp_format: Option<String>,
+    timestamp_precision: TimestampPrecision,
+    timezone: TimeZone,
+    level_aliases: [Level; 5],
+    level_colors: [ColorSpec; 5],
+    level_bg_colors: [Option<ColorSpec>; 5],
+    color_depth: ColorDepth,
+    module_filters: Vec<(String, LevelFilter)>,
+    #[cfg(feature = "regex")]
+    filters_in: Vec<Regex>,
+    #[cfg(feature = "regex")]
+    filters_out: Vec<Regex>,
+    rate_limiter: RateLimiter,
+    pre_hooks: Vec<PreHook>,
+    error_callbacks: Vec<ErrorCallback>,
+    metrics: Arc<Metrics>,
+    suppress_duplicates: bool,
+    last_record: Mutex<Option<(Level, String, String)>>,
+    repeat_count: Mutex<u64>,
+    app_name: Option<String>,
+    show_pid: bool,
+    pid: u32,
+    show_thread_id: bool,
+    show_thread_name: bool,
+    show_location: bool,
+    multiline_indent: bool,
+    max_message_length: Option<usize>,
+    truncation_suffix: String,
+    bold_level: bool,
+    underline_errors: bool,
+    timestamp_mode: TimestampMode,
+    start: Instant,
+    level_style: LevelStyle,
+    utf8_locale: bool,
+    level_padding: LevelPad,
+    module_display: ModuleDisplay,
+    target_display: TargetDisplay,
+    field_open: String,
+    field_close: String,
+    field_separator: String,
+    field_order: FieldOrder,
+    formatter: Option<Box<dyn LogFormatter>>,
+    sink: Arc<Mutex<LogSink>>,
+    level_sinks: [Option<(Arc<Mutex<LogSink>>, bool)>; 5],
+    dispatcher: Option<Dispatcher>,
+}
+
+impl fmt::Debug for Logger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Logger");
+        s.field("level", &self.level())
+            .field(
+                "enable_colors",
+                &self.inner.enable_colors.load(Ordering::Relaxed),
+            )
+            .field("colors", &self.inner.colors.load(Ordering::Relaxed))
+            .field("force_colors", &self.inner.force_colors)
+            .field("format", &self.inner.format)
+            .field("timestamp_format", &self.inner.timestamp_format)
+            .field("timestamp_precision", &self.inner.timestamp_precision)
+            .field("timezone", &self.inner.timezone)
+            .field("level_aliases", &self.inner.level_aliases)
+            .field("level_colors", &self.inner.level_colors)
+            .field("level_bg_colors", &self.inner.level_bg_colors)
+            .field("color_depth", &self.inner.color_depth)
+            .field("module_filters", &self.inner.module_filters);
+        #[cfg(feature = "regex")]
+        s.field("filters_in", &self.inner.filters_in)
+            .field("filters_out", &self.inner.filters_out);
+        // Hooks, callbacks and sinks are opaque, so only their number is shown
+        s.field("pre_hooks", &self.inner.pre_hooks.len())
+            .field("error_callbacks", &self.inner.error_callbacks.len())
+            .field("metrics", &self.inner.metrics)
+            .field("suppress_duplicates", &self.inner.suppress_duplicates)
+            .field("app_name", &self.inner.app_name)
+            .field("show_pid", &self.inner.show_pid)
+            .field("pid", &self.inner.pid)
+            .field("show_thread_id", &self.inner.show_thread_id)
+            .field("show_thread_name", &self.inner.show_thread_name)
+            .field("show_location", &self.inner.show_location)
+            .field("multiline_indent", &self.inner.multiline_indent)
+            .field("max_message_length", &self.inner.max_message_length)
+            .field("truncation_suffix", &self.inner.truncation_suffix)
+            .field("bold_level", &self.inner.bold_level)
+            .field("underline_errors", &self.inner.underline_errors)
+            .field("timestamp_mode", &self.inner.timestamp_mode)
+            .field("start", &self.inner.start)
+            .field("level_style", &self.inner.level_style)
+            .field("level_padding", &self.inner.level_padding)
+            .field("module_display", &self.inner.module_display)
+            .field("target_display", &self.inner.target_display)
+            .field("field_open", &self.inner.field_open)
+            .field("field_close", &self.inner.field_close)
+            .field("field_separator", &self.inner.field_separator)
+            .field("field_order", &self.inner.field_order)
+            .field("formatter", &self.inner.formatter.is_some())
+            .field(
+                "level_sinks",
+                &self
+                    .inner
+                    .level_sinks
+                    .iter()
+                    .filter(|s| s.is_some())
+                    .count(),
+            )
+            .field("async_dispatch", &self.inner.dispatcher.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Display for Logger {
+    /// A human readable summary like
+    /// `mowl Logger (level=Debug, colors=on, format=text)`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let format = match self.inner.format {
+            _ if self.inner.formatter.is_some() => "custom",
+            Format::Text => "text",
+            #[cfg(feature = "json")]
+            Format::Json => "json",
+            Format::Logfmt => "logfmt",
+            Format::Csv => "csv",
+            #[cfg(feature = "cbor")]
+            Format::Cbor => "cbor",
+            #[cfg(feature = "gelf")]
+            Format::Gelf { .. } => "gelf",
+        };
+        let colors = self.inner.enable_colors.load(Ordering::Relaxed)
+            && self.inner.colors.load(Ordering::Relaxed);
+        write!(
+            f,
+            "mowl Logger (level={:?}, colors={}, format={})",
+            self.level(),
+            if colors { "on" } else { "off" },
+            format
+        )
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        if metadata.level() > log::STATIC_MAX_LEVEL {
+            return false;
+        }
+        let target = metadata.target();
+        let level = self
+            .inner
+            .module_filters
+            .iter()
+            .find(|(module, _)| {
+                target.starts_with(module.as_str())
+                    && (target.len() == module.len() || target[module.len()..].starts_with("::"))
+            })
+            .map_or_else(|| self.level(), |&(_, level)| level);
+        metadata.level() <= level
+    }
+
+    fn log(&self, record: &Record) {
+        if let Err(e) = self.try_log(record) {
+            error::report_failure(record.level(), record.module_path().unwrap_or("?"), e);
+        }
+    }
+
+    fn flush(&self) {
+        if self.inner.suppress_duplicates {
+            let last = self
+                .inner
+                .last_record
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner);
+            if let Err(e) = self.log_repeated(&last) {
+                if let Some((level, ref module, _)) = *last {
+                    error::report_failure(level, module, e);
+                }
+            }
+        }
+        if let Some(ref dispatcher) = self.inner.dispatcher {
+            dispatcher.wait();
+        }
+        for sink in iter::once(&self.inner.sink)
+            .chain(self.inner.level_sinks.iter().flatten().map(|(s, _)| s))
+        {
+            let mut t = sink.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Err(e) = t.flush() {
+                error::report(format_args!("[mowl] failed to flush: {}", e));
+            }
+        }
+    }
+}
+
+impl Logger {
+    /// Creates a logger for the level writing to the output, which can be used
+    /// independently of the global logger. Use the `LoggerBuilder` for
+    /// further configuration.
+    ///
+    /// The logger takes an `Output` instead of an opened `LogSink`, since the
+    /// kind of output decides about the coloring, the CSV header and the
+    /// framing of network outputs. Opening the output may fail, hence the
+    /// `Result`.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the output cannot be opened.
+    pub fn new(level: LevelFilter, output: Output) -> Result<Self, Error> {
+        LoggerBuilder::new().level(level).output(output).build()
+    }
+
+    /// Logs a message of the module directly, without going through the `log`
+    /// crate. The module is used as module path and target of the record.
+    ///
+    /// ```
+    /// # extern crate log;
+    /// # extern crate mowl;
+    /// #
+    /// # fn main() {
+    /// let logger = mowl::Logger::new(log::LevelFilter::Info, mowl::Output::Stderr).unwrap();
+    /// logger.log_message(log::Level::Info, "app", "Directly logged").unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the record cannot be written.
+    pub fn log_message(&self, level: Level, module: &str, message: &str) -> Result<(), Error> {
+        self.try_log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target(module)
+                .module_path(Some(module))
+                .build(),
+        )
+    }
+
+    /// Writes a message of the target directly into the output, without going
+    /// through the `log` crate. Unlike `log_message`, this bypasses the
+    /// `enabled` check, so the message is written regardless of the levels,
+    /// filters and hooks of the logger. The target is used as module path and
+    /// target of the record.
+    ///
+    /// ```
+    /// # extern crate log;
+    /// # extern crate mowl;
+    /// #
+    /// # fn main() {
+    /// let logger = mowl::Logger::new(log::LevelFilter::Off, mowl::Output::Stderr).unwrap();
+    /// logger.log_direct(log::Level::Info, "app", "Always logged").unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the record cannot be written.
+    pub fn log_direct(&self, level: Level, target: &str, message: &str) -> Result<(), Error> {
+        self.log_result(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target(target)
+                .module_path(Some(target))
+                .build(),
+        )
+    }
+
+    /// Logs the record if it is enabled and passes all filters and hooks.
+    fn try_log(&self, record: &Record) -> Result<(), Error> {
+        if !self.enabled(record.metadata())
+            || !self.passes_filters(record)
+            || !self.inner.pre_hooks.iter().all(|hook| hook(record))
+        {
+            return Ok(());
+        }
+        let result = self.log_deduplicated(record);
+        if record.level() == Level::Error && !self.inner.error_callbacks.is_empty() {
+            let message = record.args().to_string();
+            self.inner.error_callbacks.iter().for_each(|f| f(&message));
+        }
+        result
+    }
+
+    #[cfg(feature = "regex")]
+    /// Checks the message of the record against the message filters.
+    fn passes_filters(&self, record: &Record) -> bool {
+        if self.inner.filters_in.is_empty() && self.inner.filters_out.is_empty() {
+            return true;
+        }
+        let message = record.args().to_string();
+        (self.inner.filters_in.is_empty()
+            || self.inner.filters_in.iter().any(|r| r.is_match(&message)))
+            && !self.inner.filters_out.iter().any(|r| r.is_match(&message))
+    }
+
+    #[cfg(not(feature = "regex"))]
+    fn passes_filters(&self, _: &Record) -> bool {
+        true
+    }
+
+    /// Logs the record unless it repeats the previous one.
+    fn log_deduplicated(&self, record: &Record) -> Result<(), Error> {
+        if !self.inner.suppress_duplicates {
+            return self.log_limited(record);
+        }
+        let key = (
+            record.level(),
+            record.module_path().unwrap_or("?").to_owned(),
+            record.args().to_string(),
+        );
+        let mut last = self
+            .inner
+            .last_record
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if last.as_ref() == Some(&key) {
+            *self
+                .inner
+                .repeat_count
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner) += 1;
+            return Ok(());
+        }
+        self.log_repeated(&last)?;
+        *last = Some(key);
+        self.log_limited(record)
+    }
+
+    /// Logs the number of suppressed repetitions of the last record, if any.
+    fn log_repeated(&self, last: &Option<(Level, String, String)>) -> Result<(), Error> {
+        let mut count = self
+            .inner
+            .repeat_count
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let (Some((level, module, _)), true) = (last, *count > 0) {
+            self.log_result(
+                &Record::builder()
+                    .args(format_args!("[repeated {} times]", *count))
+                    .level(*level)
+                    .target(module)
+                    .module_path(Some(module))
+                    .build(),
+            )?;
+        }
+        *count = 0;
+        Ok(())
+    }
+
+    /// Logs the record unless it exceeds the rate limit of its level.
+    fn log_limited(&self, record: &Record) -> Result<(), Error> {
+        let (suppressed, allowed) = match self.inner.rate_limiter.check(record.level()) {
+            Decision::Allow(suppressed) => (suppressed, true),
+            Decision::Drop(suppressed) => (suppressed, false),
+        };
+        if suppressed > 0 {
+            self.log_result(
+                &Record::builder()
+                    .args(format_args!("[{} messages suppressed]", suppressed))
+                    .metadata(record.metadata().clone())
+                    .module_path(record.module_path())
+                    .file(record.file())
+                    .line(record.line())
+                    .build(),
+            )?;
+        }
+        if allowed {
+            self.inner.metrics.count_logged(record.level());
+            self.log_result(record)
+        } else {
+            self.inner.metrics.count_dropped();
+            Ok(())
+        }
+    }
+
+    /// The most verbose level of the global level and all module filters.
+    fn max_level(&self) -> LevelFilter {
+        self.inner
+            .module_filters
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.level(), std::cmp::max)
+    }
+
+    /// The current global level of the logger.
+    fn level(&self) -> LevelFilter {
+        match self.inner.level.load(Ordering::Relaxed) {
+            0 => LevelFilter::Off,
+            1 => LevelFilter::Error,
+            2 => LevelFilter::Warn,
+            3 => LevelFilter::Info,
+            4 => LevelFilter::Debug,
+            _ => LevelFilter::Trace,
+        }
+    }
+
+    /// The counters of the logged and dropped records, which stay valid after
+    /// the logger has been registered as global logger.
+    pub fn metrics(&self) -> Arc<Metrics> {
+        self.inner.metrics.clone()
+    }
+
+    /// Changes the global level of the logger at runtime. Records above the
+    /// maximum level of the `log` crate (see `log::set_max_level`) are still
+    /// discarded before they reach the logger.
+    pub fn set_level(&self, level: LevelFilter) {
+        self.inner.level.store(level as usize, Ordering::Relaxed);
+    }
+
+    #[cfg(feature = "noop")]
+    /// Discards the record, since the `noop` feature is enabled.
+    ///
+    /// # Errors
+    ///
+    /// No error is ever returned.
+    pub fn log_result(&self, _: &Record) -> Result<(), Error> {
+        Ok(())
+    }
+
+    #[cfg(not(feature = "noop"))]
+    /// Writes the record into the output. Unlike `Log::log`, this bypasses the
+    /// `enabled` check as well as the filters and hooks, and returns the error
+    /// of writing the record.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the record cannot be written.
+    pub fn log_result(&self, record: &Record) -> Result<(), Error> {
+        if let Some((ref sink, enable_colors)) = self.inner.level_sinks[record.level() as usize - 1]
+        {
+            let mut t = sink.lock().unwrap_or_else(PoisonError::into_inner);
+            #[cfg(all(unix, feature = "unix"))]
+            t.start_record(record.level());
+            self.write_record(&mut t, record, enable_colors)?;
+            t.end_record()?;
+            return Ok(());
+        }
+        if let Some(ref dispatcher) = self.inner.dispatcher {
+            let memory = LogSink::Memory(vec![]);
+            let mut t = if self.inner.enable_colors.load(Ordering::Relaxed) {
+                LogSink::Ansi(Box::new(memory))
+            } else {
+                memory
+            };
+            self.write_record(
+                &mut t,
+                record,
+                self.inner.enable_colors.load(Ordering::Relaxed),
+            )?;
+            dispatcher.send(record.level(), t.into_memory());
+            return Ok(());
+        }
+        let mut t = self
+            .inner
+            .sink
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        #[cfg(all(unix, feature = "unix"))]
+        t.start_record(record.level());
+        self.write_record(
+            &mut t,
+            record,
+            self.inner.enable_colors.load(Ordering::Relaxed),
+        )?;
+        t.end_record()?;
+        Ok(())
+    }
+
+    fn write_record(
+        &self,
+        t: &mut LogSink,
+        record: &Record,
+        enable_colors: bool,
+    ) -> Result<(), Error> {
+        if let Some(ref formatter) = self.inner.formatter {
+            return write_formatted(&**formatter, t, record);
+        }
+        match self.inner.format {
+            Format::Text => self.write_text(t, record, enable_colors),
+            #[cfg(feature = "json")]
+            Format::Json => self.write_json(t, record),
+            Format::Logfmt => self.write_logfmt(t, record),
+            Format::Csv => self.write_csv(t, record),
+            #[cfg(feature = "cbor")]
+            Format::Cbor => self.write_cbor(t, record),
+            #[cfg(feature = "gelf")]
+            Format::Gelf { ref host } => self.write_gelf(t, record, host),
+        }
+    }
+
+    fn write_text(
+        &self,
+        t: &mut LogSink,
+        record: &Record,
+        enable_colors: bool,
+    ) -> Result<(), Error> {
+        let level = self.display_level(record);
+        let colors = enable_colors
+            && self.inner.colors.load(Ordering::Relaxed)
+            && (self.inner.force_colors || env_allows_colors());
+        // The visible width of the prefix in front of the message
+        let mut width = 0;
+        let mut separate = false;
+        for field in &self.inner.field_order.0 {
+            match *field {
+                Field::Level => {
+                    width += self.write_separator(t, &mut separate)?;
+                    width += self.write_level(t, level, colors)?;
+                }
+                Field::Message => {
+                    width += self.write_separator(t, &mut separate)?;
+                    self.write_text_message(t, record, level, colors, width)?;
+                }
+                field => {
+                    if let Some((color, value)) = self.field_value(field, record) {
+                        width += self.write_separator(t, &mut separate)?;
+                        let colored = colors && color.is_some();
+                        if let (true, Some(color)) = (colors, color) {
+                            t.fg(color)?;
+                        }
+                        width += self.write_field(t, value)?;
+                        if colored {
+                            // Reset to not carry the color into the next field
+                            t.reset()?;
+                        }
+                    }
+                }
+            }
+        }
+        writeln!(t)?;
+        Ok(())
+    }
+
+    /// The color and value of the fields which are written via `write_field`,
+    /// or `None` if the field is disabled.
+    fn field_value(&self, field: Field, record: &Record) -> Option<(Option<Color>, String)> {
+        match field {
+            Field::Pid if self.inner.show_pid => {
+                Some((Some(BRIGHT_BLACK), self.inner.pid.to_string()))
+            }
+            Field::Timestamp if self.inner.timestamp_mode != TimestampMode::Off => {
+                Some((Some(BRIGHT_BLACK), self.timestamp()))
+            }
+            Field::AppName => self
+                .inner
+                .app_name
+                .as_ref()
+                .map(|name| (Some(BRIGHT_MAGENTA), name.clone())),
+            Field::Module => {
+                let module = record.module_path().unwrap_or("?");
+                let module = match self.inner.module_display {
+                    ModuleDisplay::Full => Some(module),
+                    ModuleDisplay::Short => module.split("::").last(),
+                    ModuleDisplay::None => None,
+                    ModuleDisplay::OmitRoot(ref root) if module == root => None,
+                    ModuleDisplay::OmitRoot(_) => Some(module),
+                }?;
+                let value = match self.inner.target_display {
+                    TargetDisplay::Module => module.to_owned(),
+                    TargetDisplay::Target => record.target().to_owned(),
+                    TargetDisplay::Both => format!("{}/{}", record.target(), module),
+                };
+                Some((Some(BRIGHT_BLUE), value))
+            }
+            Field::ThreadId if self.inner.show_thread_name => {
+                let thread = std::thread::current();
+                let name = thread.name().unwrap_or("<anonymous>");
+                if self.inner.show_thread_id {
+                    Some((None, format!("{}/{}", name, thread_id())))
+                } else {
+                    Some((None, name.to_owned()))
+                }
+            }
+            Field::ThreadId if self.inner.show_thread_id => {
+                Some((None, format!("thread-{}", thread_id())))
+            }
+            _ => None,
+        }
+    }
+
+    /// Writes the level token of a text record and returns its visible width.
+    fn write_level(&self, t: &mut LogSink, level: Level, colors: bool) -> Result<usize, Error> {
+        if colors {
+            let index = level as usize - 1;
+            t.fg(self.inner.level_colors[index].resolve(self.inner.color_depth))?;
+            if let Some(bg) = self.inner.level_bg_colors[index] {
+                t.bg(bg.resolve(self.inner.color_depth))?;
+            }
+            if self.inner.bold_level {
+                t.attr(Attr::Bold)?;
+            }
+        }
+        let (open, close) = (&self.inner.field_open, &self.inner.field_close);
+        let token = match self.inner.level_style {
+            LevelStyle::Full => match self.inner.level_padding {
+                LevelPad::Left => format!("{}{:<5}{}", open, level, close),
+                LevelPad::Right => format!("{}{:>5}{}", open, level, close),
+                LevelPad::None => format!("{}{}{}", open, level, close),
+            },
+            LevelStyle::Symbol if colors || self.inner.utf8_locale => {
+                level_symbol(level).to_owned()
+            }
+            LevelStyle::Short | LevelStyle::Symbol => {
+                format!("{}{}{}", open, &level.as_str()[..1], close)
+            }
+        };
+        write!(t, "{}", token)?;
+        if colors {
+            // Reset before the separator to not extend the background color
+            t.reset()?;
+        }
+        Ok(token.chars().count())
+    }
+
+    /// Writes the message of a text record followed by its key-value pairs
+    /// and location. Multiline messages are indented by the `width` of the
+    /// fields in front of them.
+    fn write_text_message(
+        &self,
+        t: &mut LogSink,
+        record: &Record,
+        level: Level,
+        colors: bool,
+        width: usize,
+    ) -> Result<(), Error> {
+        let underline = colors && self.inner.underline_errors && level == Level::Error;
+        if underline {
+            t.attr(Attr::Underline(true))?;
+        }
+        if self.inner.multiline_indent {
+            let indent = format!("\n{:1$}", "", width);
+            write!(t, "{}", self.message(record).replace('\n', &indent))?;
+        } else if self.inner.max_message_length.is_some() {
+            write!(t, "{}", self.message(record))?;
+        } else {
+            self.write_message(t, record)?;
+        }
+        if underline {
+            t.reset()?;
+        }
+        #[cfg(feature = "kv")]
+        for (key, value) in kv::collect(record) {
+            write!(t, " {}={}", key, value)?;
+        }
+        for (key, value) in context::entries() {
+            write!(t, " {}={}", key, value)?;
+        }
+        if let (true, Some(file)) = (self.inner.show_location, record.file()) {
+            if colors {
+                t.fg(BRIGHT_BLACK)?;
+            }
+            match record.line() {
+                Some(line) => write!(t, " ({}:{})", file, line)?,
+                None => write!(t, " ({})", file)?,
+            }
+            if colors {
+                t.reset()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the field separator unless it is the first field of the record
+    /// and returns its visible width.
+    fn write_separator(&self, t: &mut LogSink, separate: &mut bool) -> Result<usize, Error> {
+        if !std::mem::replace(separate, true) {
+            return Ok(0);
+        }
+        t.write_all(self.inner.field_separator.as_bytes())?;
+        Ok(self.inner.field_separator.chars().count())
+    }
+
+    /// The level of the record after applying the level aliases.
+    fn display_level(&self, record: &Record) -> Level {
+        self.inner.level_aliases[record.level() as usize - 1]
+    }
+
+    /// The message of the record, truncated to the maximum message length.
+    fn message(&self, record: &Record) -> String {
+        let mut message = record.args().to_string();
+        if let Some(max) = self.inner.max_message_length {
+            if message.len() > max {
+                let mut end = max;
+                while !message.is_char_boundary(end) {
+                    end -= 1;
+                }
+                message.truncate(end);
+                message.push_str(&self.inner.truncation_suffix);
+            }
+        }
+        message
+    }
+
+    #[cfg(feature = "fast-format")]
+    /// Writes the message of the record in a single call to the sink. Messages
+    /// without arguments are written as they are and short messages are
+    /// formatted into a buffer on the stack, so that no allocation is needed.
+    fn write_message(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        use std::fmt::Write;
+        if let Some(message) = record.args().as_str() {
+            return Ok(t.write_all(message.as_bytes())?);
+        }
+        let mut buffer = arrayvec::ArrayString::<256>::new();
+        if buffer.write_fmt(*record.args()).is_ok() {
+            t.write_all(buffer.as_bytes())?;
+        } else {
+            write!(t, "{}", record.args())?;
+        }
+        Ok(())
+    }
+
+    #[cfg(not(feature = "fast-format"))]
+    fn write_message(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        Ok(write!(t, "{}", record.args())?)
+    }
+
+    /// Writes a single delimited field of a text record without the separator
+    /// and returns its visible width.
+    fn write_field<T: Display>(&self, t: &mut LogSink, value: T) -> Result<usize, Error> {
+        let field = format!(
+            "{}{}{}",
+            self.inner.field_open, value, self.inner.field_close
+        );
+        t.write_all(field.as_bytes())?;
+        Ok(field.chars().count())
+    }
+
+    #[cfg(feature = "json")]
+    fn write_json(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        let level = self.display_level(record);
+        let mut object = json!({
+            "level": level.as_str(),
+            "module": record.module_path().unwrap_or("?"),
+            "message": self.message(record),
+        });
+        if self.inner.timestamp_mode != TimestampMode::Off {
+            object["timestamp"] = self.timestamp().into();
+        }
+        if let Some(ref name) = self.inner.app_name {
+            object["app"] = name.as_str().into();
+        }
+        #[cfg(feature = "kv")]
+        for (key, value) in kv::collect(record) {
+            object[key.as_str()] = kv::to_json(&value);
+        }
+        for (key, value) in context::entries() {
+            object[key] = value.into();
+        }
+        serde_json::to_writer(&mut *t, &object).map_err(std::io::Error::from)?;
+        writeln!(t)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "cbor")]
+    fn write_cbor(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        use ciborium::value::Value;
+
+        let level = self.display_level(record);
+        let mut map = vec![
+            ("level".into(), level.as_str().into()),
+            ("module".into(), record.module_path().unwrap_or("?").into()),
+            ("message".into(), self.message(record).into()),
+        ];
+        if self.inner.timestamp_mode != TimestampMode::Off {
+            map.push(("timestamp".into(), self.timestamp().into()));
+        }
+        if let Some(ref name) = self.inner.app_name {
+            map.push(("app".into(), name.as_str().into()));
+        }
+        #[cfg(feature = "kv")]
+        for (key, value) in kv::collect(record) {
+            map.push((key.as_str().into(), value.to_string().into()));
+        }
+        for (key, value) in context::entries() {
+            map.push((key.into(), value.into()));
+        }
+        ciborium::ser::into_writer(&Value::Map(map), &mut *t)
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+        Ok(())
+    }
+
+    #[cfg(feature = "gelf")]
+    fn write_gelf(&self, t: &mut LogSink, record: &Record, host: &str) -> Result<(), Error> {
+        let message = self.message(record);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs_f64();
+        let mut object = json!({
+            "version": "1.1",
+            "host": host,
+            "short_message": message.lines().next().unwrap_or_default(),
+            "full_message": message,
+            "timestamp": timestamp,
+            "level": syslog::severity(self.display_level(record)),
+            "_module": record.module_path().unwrap_or("?"),
+        });
+        if let Some(file) = record.file() {
+            object["_file"] = file.into();
+        }
+        if let Some(line) = record.line() {
+            object["_line"] = line.into();
+        }
+        if let Some(ref name) = self.inner.app_name {
+            object["_app"] = name.as_str().into();
+        }
+        #[cfg(feature = "kv")]
+        for (key, value) in kv::collect(record) {
+            object[format!("_{}", key)] = value.to_string().into();
+        }
+        for (key, value) in context::entries() {
+            object[format!("_{}", key)] = value.into();
+        }
+        serde_json::to_writer(&mut *t, &object).map_err(std::io::Error::from)?;
+        writeln!(t)?;
+        Ok(())
+    }
+
+    fn write_logfmt(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        let level = self.display_level(record);
+        if self.inner.timestamp_mode != TimestampMode::Off {
+            write!(t, "time={} ", logfmt_value(&self.timestamp()))?;
+        }
+        if let Some(ref name) = self.inner.app_name {
+            write!(t, "app={} ", logfmt_value(name))?;
+        }
+        write!(
+            t,
+            "level={} module={} msg=\"{}\"",
+            level,
+            logfmt_value(record.module_path().unwrap_or("?")),
+            logfmt_escape(&self.message(record)),
+        )?;
+        #[cfg(feature = "kv")]
+        for (key, value) in kv::collect(record) {
+            write!(t, " {}={}", key, logfmt_value(&value.to_string()))?;
+        }
+        for (key, value) in context::entries() {
+            write!(t, " {}={}", key, logfmt_value(&value))?;
+        }
+        writeln!(t)?;
+        Ok(())
+    }
+
+    fn write_csv(&self, t: &mut LogSink, record: &Record) -> Result<(), Error> {
+        let level = self.display_level(record);
+        if self.inner.timestamp_mode != TimestampMode::Off {
+            write!(t, "{},", csv_field(&self.timestamp()))?;
+        }
+        writeln!(
+            t,
+            "{},{},{}",
+            level,
+            csv_field(record.module_path().unwrap_or("?")),
+            csv_field(&self.message(record)),
+        )?;
+        Ok(())
+    }
+
+    fn timestamp(&self) -> String {
+        if self.inner.timestamp_mode == TimestampMode::Elapsed {
+            return format!("+{}", format::format_elapsed(self.inner.start.elapsed()));
+        }
+        if let (None, TimestampPrecision::Microseconds | TimestampPrecision::Nanoseconds) =
+            (&self.inner.timestamp_format, self.inner.timestamp_precision)
+        {
+            return subsecond_timestamp(self.inner.timestamp_precision);
+        }
+        clock::timestamp(self.inner.timestamp_format.as_deref(), self.inner.timezone)
+    }
+
+    /// Disable coloring output, which affects all clones of the logger
+    pub fn disable_colors(&mut self) {
+        self.inner.enable_colors.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(feature = "validate-utf8"))]
+/// Writes the record by a custom formatter.
+fn write_formatted(
+    formatter: &dyn LogFormatter,
+    t: &mut LogSink,
+    record: &Record,
+) -> Result<(), Error> {
+    formatter.format(record, t)
+}
+
+#[cfg(feature = "validate-utf8")]
+/// Writes the record by a custom formatter, which is rendered into memory
+/// first. The built-in formats are always valid UTF-8, but the output of a
+/// custom formatter may be not. In this case, the record is written by the
+/// `TextFormatter` instead, with a hex dump of the first 32 bytes of the
+/// rendered record as message, since the custom formatter may produce the
+/// same invalid output again.
+fn write_formatted(
+    formatter: &dyn LogFormatter,
+    t: &mut LogSink,
+    record: &Record,
+) -> Result<(), Error> {
+    let mut buffer = vec![];
+    formatter.format(record, &mut buffer)?;
+    if std::str::from_utf8(&buffer).is_err() {
+        let hex = buffer
+            .iter()
+            .take(32)
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        buffer.clear();
+        TextFormatter.format(
+            &Record::builder()
+                .args(format_args!("<invalid utf-8 in log message> {}", hex))
+                .metadata(record.metadata().clone())
+                .module_path(record.module_path())
+                .file(record.file())
+                .line(record.line())
+                .build(),
+            &mut buffer,
+        )?;
+    }
+    t.write_all(&buffer)?;
+    Ok(())
+}
+
+/// Resolves the first socket address of a network output.
+fn resolve(addr: &str) -> Result<SocketAddr, Error> {
+    addr.to_socket_addrs()?.next().ok_or_else(|| {
+        io::Error::new(io::ErrorKind::InvalidInput, "address does not resolve").into()
+    })
+}
+
+/// Formats the current time as RFC 3339 timestamp in UTC with microseconds or
+/// nanoseconds.
+fn subsecond_timestamp(precision: TimestampPrecision) -> String {
+    let now = clock::DateTime::now();
+    let seconds = now.format("%FT%T");
+    if precision == TimestampPrecision::Nanoseconds {
+        format!("{}.{:09}Z", seconds, now.nanosecond())
+    } else {
+        format!("{}.{:06}Z", seconds, now.nanosecond() / 1000)
+    }
+}
+
+/// Retrieves the numeric id of the current thread, which is only available via
+/// its `Debug` representation `ThreadId(N)`.
+fn thread_id() -> String {
+    format!("{:?}", std::thread::current().id())
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect()
+}
+
+/// Checks whether the environment allows colored output, which is not the case
+/// if `NO_COLOR` is set (see <https://no-color.org>) or if `TERM` is `dumb`.
+fn env_allows_colors() -> bool {
+    env::var_os("NO_COLOR").is_none() && env::var_os("TERM").as_deref() != Some(OsStr::new("dumb"))
+}
+
+/// Escapes backslashes, double quotes and newlines of a logfmt value.
+fn logfmt_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Quotes a logfmt value if required.
+fn logfmt_value(value: &str) -> String {
+    if value.is_empty() || value.contains(&[' ', '=', '"'][..]) {
+        format!("\"{}\"", logfmt_escape(value))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains(&[',', '"', '\n', '\r'][..]) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+/// The unicode symbol of a level.
+fn level_symbol(level: Level) -> &'static str {
+    match level {
+        Level::Error => "✖",
+        Level::Warn => "⚠",
+        Level::Info => "ℹ",
+        Level::Debug => "⚙",
+        Level::Trace => "·",
+    }
+}
+
+/// Checks whether the locale indicates UTF-8 support.
+fn utf8_locale() -> bool {
+    ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|v| env::var(v).ok())
+        .find(|v| !v.is_empty())
+        .map(|v| v.to_lowercase())
+        .is_some_and(|v| v.contains("utf-8") || v.contains("utf8"))
 }
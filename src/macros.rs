@@ -0,0 +1,110 @@
+//! Logging macros which can be used instead of the ones of the `log` crate
+//!
+//! ```
+//! #[macro_use]
+//! extern crate mowl;
+//!
+//! # fn main() {
+//! mowl::init().unwrap();
+//! warn!("Warning");
+//! info!(target: "custom", "Information");
+//! # }
+//! ```
+
+/// Logs a message at the specified level.
+///
+/// The record contains the module path, file and line of the call site, and
+/// the module path is used as target unless specified via `target:`.
+#[macro_export]
+macro_rules! log {
+    (@level $level:expr, target: $target:expr, $($arg:tt)+) => {
+        $crate::log!(target: $target, $level, $($arg)+)
+    };
+    (@level $level:expr, $($arg:tt)+) => {
+        $crate::log!(target: module_path!(), $level, $($arg)+)
+    };
+    (target: $target:expr, $level:expr, $($arg:tt)+) => {{
+        let level = $level;
+        if level <= $crate::__private::STATIC_MAX_LEVEL && level <= $crate::__private::max_level() {
+            $crate::__private::log(
+                format_args!($($arg)+),
+                level,
+                $target,
+                module_path!(),
+                file!(),
+                line!(),
+            );
+        }
+    }};
+    ($level:expr, $($arg:tt)+) => {
+        $crate::log!(target: module_path!(), $level, $($arg)+)
+    };
+}
+
+/// Logs a message at the error level.
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::log!(@level $crate::__private::Level::Error, $($arg)+)
+    };
+}
+
+/// Logs a message at the warn level.
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::log!(@level $crate::__private::Level::Warn, $($arg)+)
+    };
+}
+
+/// Logs a message at the info level.
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log!(@level $crate::__private::Level::Info, $($arg)+)
+    };
+}
+
+/// Logs a message at the debug level.
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!(@level $crate::__private::Level::Debug, $($arg)+)
+    };
+}
+
+/// Logs a message at the trace level.
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!(@level $crate::__private::Level::Trace, $($arg)+)
+    };
+}
+
+#[doc(hidden)]
+pub mod __private {
+    use log::{logger, Record};
+    use std::fmt::Arguments;
+
+    pub use log::{max_level, Level, STATIC_MAX_LEVEL};
+
+    pub fn log(
+        args: Arguments,
+        level: Level,
+        target: &str,
+        module_path: &'static str,
+        file: &'static str,
+        line: u32,
+    ) {
+        logger().log(
+            &Record::builder()
+                .args(args)
+                .level(level)
+                .target(target)
+                .module_path_static(Some(module_path))
+                .file_static(Some(file))
+                .line(Some(line))
+                .build(),
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Counters of the logged records
+use log::Level;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The number of records logged per level and dropped by rate limiting.
+///
+/// ```
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// let logger = mowl::LoggerBuilder::new().build().unwrap();
+/// let metrics = logger.metrics();
+/// assert_eq!(metrics.errors_logged(), 0);
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct Metrics {
+    logged: [AtomicU64; 5],
+    dropped: AtomicU64,
+}
+
+impl Metrics {
+    /// The number of logged records of a level.
+    pub fn logged(&self, level: Level) -> u64 {
+        self.logged[level as usize - 1].load(Ordering::Relaxed)
+    }
+
+    /// The number of logged `Error` records.
+    pub fn errors_logged(&self) -> u64 {
+        self.logged(Level::Error)
+    }
+
+    /// The number of logged `Warn` records.
+    pub fn warns_logged(&self) -> u64 {
+        self.logged(Level::Warn)
+    }
+
+    /// The number of logged `Info` records.
+    pub fn infos_logged(&self) -> u64 {
+        self.logged(Level::Info)
+    }
+
+    /// The number of logged `Debug` records.
+    pub fn debugs_logged(&self) -> u64 {
+        self.logged(Level::Debug)
+    }
+
+    /// The number of logged `Trace` records.
+    pub fn traces_logged(&self) -> u64 {
+        self.logged(Level::Trace)
+    }
+
+    /// The number of records dropped by rate limiting.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn count_logged(&self, level: Level) {
+        self.logged[level as usize - 1].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn count_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
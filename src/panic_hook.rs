@@ -0,0 +1,52 @@
+//! Logging of panics through the global logger
+use log::{Level, Record};
+use std::{panic, thread};
+
+/// Installs a panic hook which logs the panic message and location as `Error`
+/// record through the global logger, before the previously installed hook is
+/// called. This keeps panics in the log output, for example if only the log
+/// file is inspected.
+///
+/// ```
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::init().unwrap();
+/// mowl::install_panic_hook();
+/// # }
+/// ```
+pub fn install_panic_hook() {
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = match payload.downcast_ref::<&str>() {
+            Some(message) => (*message).to_owned(),
+            None => match payload.downcast_ref::<String>() {
+                Some(message) => message.clone(),
+                None => format!("{:?}", payload),
+            },
+        };
+        let thread = thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        let location = info.location();
+        let logger = log::logger();
+        logger.log(
+            &Record::builder()
+                .args(format_args!(
+                    "thread '{}' panicked at {}: {}",
+                    name,
+                    location
+                        .map_or_else(|| "?".to_owned(), |l| format!("{}:{}", l.file(), l.line())),
+                    message
+                ))
+                .level(Level::Error)
+                .target("panic")
+                .module_path(Some("panic"))
+                .file(location.map(|l| l.file()))
+                .line(location.map(|l| l.line()))
+                .build(),
+        );
+        logger.flush();
+        previous(info);
+    }));
+}
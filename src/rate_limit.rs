@@ -0,0 +1,78 @@
+//! Per level rate limiting of log records
+use log::Level;
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// The length of a rate limiting window.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// The outcome of a rate limit check.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Decision {
+    /// The record should be logged, after a notice about the given number of
+    /// records suppressed in the previous window.
+    Allow(u64),
+    /// The record exceeds the quota and should be dropped, after a notice about
+    /// the given number of records suppressed in the previous window.
+    Drop(u64),
+}
+
+/// The state of a single level within the current window.
+#[derive(Clone, Copy)]
+struct Window {
+    start: Instant,
+    count: u32,
+    dropped: u64,
+}
+
+/// Limits the number of records per level and second.
+pub(crate) struct RateLimiter {
+    limits: [Option<u32>; 5],
+    windows: Mutex<[Window; 5]>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter with the maximum records per second for
+    /// each level, indexed by `level as usize - 1`.
+    pub(crate) fn new(limits: [Option<u32>; 5]) -> Self {
+        let window = Window {
+            start: Instant::now(),
+            count: 0,
+            dropped: 0,
+        };
+        Self {
+            limits,
+            windows: Mutex::new([window; 5]),
+        }
+    }
+
+    /// Counts a record of the given level against its quota.
+    pub(crate) fn check(&self, level: Level) -> Decision {
+        let index = level as usize - 1;
+        let max = match self.limits[index] {
+            Some(max) => max,
+            None => return Decision::Allow(0),
+        };
+        let mut windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        let window = &mut windows[index];
+        let now = Instant::now();
+        let mut suppressed = 0;
+        if now.duration_since(window.start) >= WINDOW {
+            suppressed = window.dropped;
+            *window = Window {
+                start: now,
+                count: 0,
+                dropped: 0,
+            };
+        }
+        if window.count < max {
+            window.count += 1;
+            Decision::Allow(suppressed)
+        } else {
+            window.dropped += 1;
+            Decision::Drop(suppressed)
+        }
+    }
+}
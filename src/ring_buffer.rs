@@ -0,0 +1,89 @@
+//! Keeping the most recent records in memory for post-mortem retrieval
+//!
+//! ```
+//! # #[macro_use] extern crate log;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! let (result, records) = mowl::ring_buffer::init(100, log::LevelFilter::Info);
+//! result.unwrap();
+//! info!("Kept in memory");
+//!
+//! # #[cfg(not(feature = "noop"))]
+//! assert!(records.lock().unwrap()[0].ends_with("Kept in memory"));
+//! # }
+//! ```
+use error::Error;
+use log::LevelFilter;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+use {ColorMode, LoggerBuilder, Output};
+
+/// The records shared between the logger and the caller.
+pub type Records = Arc<Mutex<VecDeque<String>>>;
+
+/// Installs a logger keeping the last `capacity` records in memory and returns
+/// the records together with the result of the installation. The records do
+/// not contain a trailing newline.
+pub fn init(capacity: usize, level: LevelFilter) -> (Result<(), Error>, Records) {
+    let ring_buffer = RingBuffer::new(capacity);
+    let records = ring_buffer.records();
+    let result = LoggerBuilder::new()
+        .level(level)
+        .output(Output::RingBuffer(ring_buffer))
+        .color_mode(ColorMode::Never)
+        .init();
+    (result, records)
+}
+
+/// A bounded buffer of records, which drops the oldest record when it is full.
+#[derive(Clone)]
+pub struct RingBuffer {
+    records: Records,
+    capacity: usize,
+}
+
+impl RingBuffer {
+    /// Creates an empty buffer for at most `capacity` records.
+    pub fn new(capacity: usize) -> Self {
+        RingBuffer {
+            records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    /// Returns a handle to the records of the buffer.
+    pub fn records(&self) -> Records {
+        self.records.clone()
+    }
+
+    /// Appends a record, dropping the oldest one if the buffer is full.
+    pub(crate) fn push(&self, record: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap_or_else(PoisonError::into_inner);
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+}
+
+impl fmt::Debug for RingBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+/// Buffers are equal if they share the same records.
+impl PartialEq for RingBuffer {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.records, &other.records)
+    }
+}
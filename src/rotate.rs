@@ -0,0 +1,240 @@
+//! Files which are rotated once they grow too large or a day has passed
+use clock::{self, DateTime};
+#[cfg(feature = "gzip")]
+use error;
+use error::Error;
+#[cfg(feature = "gzip")]
+use flate2::{write::GzEncoder, Compression};
+use sink::open_append;
+#[cfg(feature = "gzip")]
+use std::thread::{self, JoinHandle};
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+/// A log file which is renamed to `<path>.1` once it exceeds `max_bytes`,
+/// shifting older files to `<path>.2`, `<path>.3` and so on. At most
+/// `max_files` rotated files are kept.
+pub struct RotatingFileSink {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    current_size: u64,
+    current_file: BufWriter<File>,
+    #[cfg(feature = "gzip")]
+    compress: bool,
+    #[cfg(feature = "gzip")]
+    compression: Option<JoinHandle<()>>,
+}
+
+impl RotatingFileSink {
+    pub(crate) fn new(path: &Path, max_bytes: u64, max_files: usize) -> Result<Self, Error> {
+        let file = open_append(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_bytes,
+            max_files,
+            current_size: file.metadata()?.len(),
+            current_file: BufWriter::new(file),
+            #[cfg(feature = "gzip")]
+            compress: false,
+            #[cfg(feature = "gzip")]
+            compression: None,
+        })
+    }
+
+    #[cfg(feature = "gzip")]
+    /// Compresses the rotated files into `<path>.1.gz` and so on.
+    pub(crate) fn enable_compression(&mut self) {
+        self.compress = true;
+    }
+
+    /// Rotates the files and opens a new, empty file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.current_file.flush()?;
+        // The previous compression must not race with shifting its output
+        #[cfg(feature = "gzip")]
+        if let Some(compression) = self.compression.take() {
+            let _ = compression.join();
+        }
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for index in (1..self.max_files).rev() {
+                for suffix in ROTATED_SUFFIXES {
+                    let from = rotated_path(&self.path, index, suffix);
+                    if from.exists() {
+                        fs::rename(from, rotated_path(&self.path, index + 1, suffix))?;
+                    }
+                }
+            }
+            let rotated = rotated_path(&self.path, 1, "");
+            fs::rename(&self.path, &rotated)?;
+            #[cfg(feature = "gzip")]
+            if self.compress {
+                self.compression = Some(thread::spawn(move || {
+                    if let Err(e) = compress(&rotated) {
+                        error::report(format_args!(
+                            "[mowl] failed to compress {}: {}",
+                            rotated.display(),
+                            Error::Io(e)
+                        ));
+                    }
+                }));
+            }
+        }
+        let file = open_append(&self.path).map_err(|e| io::Error::other(e.to_string()))?;
+        self.current_file = BufWriter::new(file);
+        self.current_size = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl Drop for RotatingFileSink {
+    /// Waits for a running compression, which would leave a truncated archive
+    /// behind if the process exits in the meantime.
+    fn drop(&mut self) {
+        if let Some(compression) = self.compression.take() {
+            let _ = compression.join();
+        }
+    }
+}
+
+impl Write for RotatingFileSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.current_file.write(buf)?;
+        self.current_size += written as u64;
+        Ok(written)
+    }
+
+    /// Flushes the record and rotates the files if the size is exceeded, so
+    /// that records are never split across files.
+    fn flush(&mut self) -> io::Result<()> {
+        self.current_file.flush()?;
+        if self.current_size > self.max_bytes {
+            self.rotate()?;
+        }
+        Ok(())
+    }
+}
+
+/// A log file which is renamed to `<path>.YYYY-MM-DD` once the UTC date
+/// changes. At most `max_days` of these historical files are kept.
+pub struct TimedRotatingSink {
+    path: PathBuf,
+    max_days: usize,
+    current_date: String,
+    current_file: BufWriter<File>,
+    record_started: bool,
+}
+
+impl TimedRotatingSink {
+    pub(crate) fn new(path: &Path, max_days: usize) -> Result<Self, Error> {
+        let file = open_append(path)?;
+        // An existing file belongs to the day it was last written to
+        let modified = DateTime::from(file.metadata()?.modified()?).date();
+        Ok(Self {
+            path: path.to_path_buf(),
+            max_days,
+            current_date: modified,
+            current_file: BufWriter::new(file),
+            record_started: false,
+        })
+    }
+
+    /// Rotates the file if the UTC date changed since it was opened.
+    fn rotate_if_needed(&mut self) -> io::Result<()> {
+        let today = today();
+        if today == self.current_date {
+            return Ok(());
+        }
+        self.current_file.flush()?;
+        let mut rotated = self.path.as_os_str().to_owned();
+        rotated.push(format!(".{}", self.current_date));
+        fs::rename(&self.path, rotated)?;
+        let file = open_append(&self.path).map_err(|e| io::Error::other(e.to_string()))?;
+        self.current_file = BufWriter::new(file);
+        self.current_date = today;
+        self.remove_old_files()
+    }
+
+    /// Removes the oldest historical files exceeding `max_days`.
+    fn remove_old_files(&self) -> io::Result<()> {
+        let name = match self.path.file_name().and_then(|n| n.to_str()) {
+            Some(name) => format!("{}.", name),
+            None => return Ok(()),
+        };
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut historical = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|n| n.to_str())
+                    .and_then(|n| n.strip_prefix(name.as_str()))
+                    .is_some_and(clock::is_date)
+            })
+            .collect::<Vec<_>>();
+        // ISO dates sort chronologically
+        historical.sort();
+        let excess = historical.len().saturating_sub(self.max_days);
+        for path in historical.into_iter().take(excess) {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for TimedRotatingSink {
+    /// Rotates the file before the first write of a record, so that records
+    /// are never split across files.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.record_started {
+            self.rotate_if_needed()?;
+            self.record_started = true;
+        }
+        self.current_file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.record_started = false;
+        self.current_file.flush()
+    }
+}
+
+/// The current date in UTC, which is not affected by daylight saving time.
+fn today() -> String {
+    DateTime::now().date()
+}
+
+#[cfg(not(feature = "gzip"))]
+/// The suffixes of the rotated files following their index.
+const ROTATED_SUFFIXES: [&str; 1] = [""];
+
+#[cfg(feature = "gzip")]
+/// The suffixes of the rotated files following their index.
+const ROTATED_SUFFIXES: [&str; 2] = ["", ".gz"];
+
+/// The path of the rotated file with the index, like `mowl.log.1`.
+fn rotated_path(path: &Path, index: usize, suffix: &str) -> PathBuf {
+    let mut rotated = path.as_os_str().to_owned();
+    rotated.push(format!(".{}{}", index, suffix));
+    PathBuf::from(rotated)
+}
+
+#[cfg(feature = "gzip")]
+/// Compresses the file into a `.gz` file next to it and removes the original.
+fn compress(path: &Path) -> io::Result<()> {
+    let mut compressed = path.as_os_str().to_owned();
+    compressed.push(".gz");
+    let mut encoder = GzEncoder::new(File::create(compressed)?, Compression::default());
+    io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
@@ -0,0 +1,990 @@
+//! Output destinations of the logger
+use color::ColorSpec;
+use error::Error;
+use gelf;
+#[cfg(all(unix, feature = "unix"))]
+use log::Level;
+use ring_buffer::RingBuffer;
+use rotate::{RotatingFileSink, TimedRotatingSink};
+#[cfg(all(unix, feature = "unix"))]
+use std::os::unix::net::UnixDatagram;
+use std::{
+    env, fmt,
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, IsTerminal, Write},
+    net::{SocketAddr, TcpStream, UdpSocket},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+#[cfg(all(unix, feature = "unix"))]
+use syslog::{self, SyslogFacility};
+use term::{Attr, StderrTerminal, StdoutTerminal};
+
+/// The output destination of the logger.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Output {
+    /// Write to stderr, which is the default
+    #[default]
+    Stderr,
+    /// Write to stdout
+    Stdout,
+    /// Append to a file without any coloring
+    File(PathBuf),
+    /// Append to a file, which is renamed to `<path>.1` once it exceeds
+    /// `max_bytes`, shifting older files to `<path>.2` and so on
+    RotatingFile {
+        /// The path of the current log file
+        path: PathBuf,
+        /// The size above which the file is rotated after a record
+        max_bytes: u64,
+        /// The number of rotated files to keep
+        max_files: usize,
+    },
+    /// Append to a file, which is renamed to `<path>.YYYY-MM-DD` once the
+    /// UTC date changes
+    TimedRotatingFile {
+        /// The path of the current log file
+        path: PathBuf,
+        /// The number of historical files to keep
+        max_days: usize,
+    },
+    #[cfg(all(unix, feature = "unix"))]
+    /// Send each record as datagram to the local syslog daemon
+    Syslog {
+        /// The facility of the records
+        facility: SyslogFacility,
+        /// The path of the syslog socket, `/dev/log` or `/var/run/syslog` are
+        /// used if not set
+        socket: Option<PathBuf>,
+    },
+    /// Send each record as datagram to a remote address, for example a log
+    /// aggregator. UDP is unreliable, so records may get lost without notice,
+    /// which makes this output suitable for development and debugging only.
+    Udp(SocketAddr),
+    /// Send the records as newline terminated lines via a TCP connection,
+    /// which is established on the first record and re-established if the
+    /// connection breaks
+    Tcp(SocketAddr),
+    /// Keep the most recent records in memory, see `ring_buffer`
+    RingBuffer(RingBuffer),
+    /// Write to a custom writer without any coloring
+    Writer(SharedWriter),
+    #[cfg(all(unix, feature = "unix"))]
+    /// Write to a named pipe (FIFO), which blocks until a reader opened it.
+    /// The output falls back to stderr once the reader went away.
+    NamedPipe(PathBuf),
+}
+
+/// A custom writer shared between the caller and the logger.
+///
+/// ```
+/// # extern crate mowl;
+/// let writer = mowl::SharedWriter::new(std::io::sink());
+/// let logger = mowl::LoggerBuilder::new()
+///     .output(mowl::Output::Writer(writer))
+///     .build()
+///     .unwrap();
+/// ```
+#[derive(Clone)]
+pub struct SharedWriter(Arc<Mutex<dyn Write + Send>>);
+
+impl SharedWriter {
+    /// Wraps the writer to be used as `Output::Writer`.
+    pub fn new<W: Write + Send + 'static>(writer: W) -> Self {
+        SharedWriter(Arc::new(Mutex::new(writer)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, dyn Write + Send + 'static> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for SharedWriter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SharedWriter")
+    }
+}
+
+impl PartialEq for SharedWriter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+/// Controls how the output is buffered before it is written.
+///
+/// Buffering reduces the number of system calls, which improves the
+/// throughput at the cost of latency. With `BufferMode::Block`, records appear
+/// only once the block is full or the logger is flushed, for example via
+/// `log::logger().flush()`, and records which are still buffered on process
+/// exit are lost. Colors of buffered terminal output are written as raw ANSI
+/// escape sequences.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BufferMode {
+    /// Write every part of a record directly, which is the default
+    #[default]
+    None,
+    /// Collect each record and write it at once
+    Line,
+    /// Collect records until at least the given number of bytes is buffered
+    Block(usize),
+}
+
+/// Different output implementations for the logger.
+///
+/// A sink can be created by `new_terminal`, `new_stderr` or `new_file` to
+/// write colored output outside of the logger, for example within a
+/// `LogFormatter`. The variants are implementation details of the outputs and
+/// may change in any release.
+///
+/// ```
+/// # extern crate mowl;
+/// # extern crate term;
+/// # use std::io::Write;
+/// use mowl::LogSink;
+///
+/// # fn main() {
+/// let mut sink = LogSink::new_terminal();
+/// sink.fg(term::color::GREEN).unwrap();
+/// write!(sink, "Done").unwrap();
+/// sink.reset().unwrap();
+/// writeln!(sink).unwrap();
+/// # }
+/// ```
+#[non_exhaustive]
+pub enum LogSink {
+    /// Stderr Terminal as default
+    Terminal(Box<StderrTerminal>),
+    /// Stderr as fallback if a terminal cannot be instantiated
+    Fallback(std::io::Stderr),
+    /// Stdout Terminal
+    Stdout(Box<StdoutTerminal>),
+    /// Stdout as fallback if a terminal cannot be instantiated
+    StdoutFallback(std::io::Stdout),
+    /// A file opened in append mode
+    File(BufWriter<File>),
+    /// A file which is rotated by size
+    Rotating(RotatingFileSink),
+    /// A custom writer
+    Writer(SharedWriter),
+    /// A file which is rotated daily
+    TimedRotating(TimedRotatingSink),
+    /// Multiple sinks receiving the same output
+    Tee(MultiSink),
+    /// A sink without terminal support which gets colored by raw ANSI escape
+    /// sequences
+    Ansi(Box<LogSink>),
+    /// A sink which collects the output in memory before writing it
+    Buffered(BufferedSink),
+    /// An in-memory buffer to render records into
+    Memory(Vec<u8>),
+    #[cfg(all(unix, feature = "unix"))]
+    /// The syslog socket, which sends the buffered record on flush
+    Syslog {
+        /// The connected syslog socket
+        socket: UnixDatagram,
+        /// The numeric facility of the records
+        facility: u8,
+        /// The numeric severity of the current record
+        severity: u8,
+        /// The current record
+        buffer: Vec<u8>,
+    },
+    /// A UDP socket, which sends the buffered record on flush, as GELF
+    /// chunks if `gelf` is set
+    Udp {
+        /// The local socket
+        socket: UdpSocket,
+        /// The destination address
+        addr: SocketAddr,
+        /// The current record
+        buffer: Vec<u8>,
+        /// Whether the record is sent as GELF chunks
+        gelf: bool,
+    },
+    /// The most recent records, which receive the buffered record on flush
+    RingBuffer {
+        /// The shared ring buffer
+        ring: RingBuffer,
+        /// The current record
+        buffer: Vec<u8>,
+    },
+    #[cfg(all(unix, feature = "unix"))]
+    /// A named pipe, which gets replaced by stderr if the pipe breaks
+    NamedPipe(BufWriter<File>),
+    /// A TCP connection, which sends the buffered record on flush, null
+    /// terminated instead of newline terminated if `gelf` is set
+    Tcp {
+        /// The connection, which is established on the first record
+        stream: Option<TcpStream>,
+        /// The destination address
+        addr: SocketAddr,
+        /// The delay before the next reconnection attempt, which doubles with
+        /// every failed attempt
+        reconnect_delay: Duration,
+        /// The time before which records are dropped without an attempt to
+        /// reconnect
+        retry_at: Option<Instant>,
+        /// The current record
+        buffer: Vec<u8>,
+        /// Whether the record is null terminated as GELF
+        gelf: bool,
+    },
+}
+
+/// The maximum payload of a UDP datagram over IPv4.
+const MAX_UDP_PAYLOAD: usize = 65_507;
+
+impl Output {
+    /// Checks whether the output starts empty, which is the case for all
+    /// outputs except files that already contain records.
+    fn is_empty(&self) -> bool {
+        match *self {
+            Output::File(ref path)
+            | Output::RotatingFile { ref path, .. }
+            | Output::TimedRotatingFile { ref path, .. } => {
+                fs::metadata(path).map_or(true, |m| m.len() == 0)
+            }
+            _ => true,
+        }
+    }
+}
+
+/// The delay before reconnecting a broken TCP connection for the first time.
+const TCP_INITIAL_DELAY: Duration = Duration::from_millis(100);
+
+/// The maximum delay between two attempts to reconnect a TCP connection.
+const TCP_MAX_DELAY: Duration = Duration::from_secs(30);
+
+impl LogSink {
+    /// Creates the sink for the outputs. If `force_colors` is set, then sinks
+    /// without terminal support are colored by ANSI escape sequences. Colors
+    /// forced by the environment only apply to stderr and stdout. The header
+    /// is written to every sink which does not append to a non-empty file.
+    pub(crate) fn new(
+        outputs: &[Output],
+        force_colors: bool,
+        buffer_mode: BufferMode,
+        header: Option<&str>,
+    ) -> Result<Self, Error> {
+        let mut sinks = outputs
+            .iter()
+            .map(|o| {
+                let empty = o.is_empty();
+                let mut sink = Self::from_output(o, force_colors)?;
+                if let (true, Some(header)) = (empty, header) {
+                    sink.write_all(header.as_bytes())?;
+                    sink.flush()?;
+                }
+                Ok(sink.buffered(buffer_mode))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        if sinks.len() == 1 {
+            Ok(sinks.remove(0))
+        } else {
+            Ok(Self::Tee(MultiSink {
+                sinks,
+                errors: vec![],
+            }))
+        }
+    }
+
+    fn from_output(output: &Output, force_colors: bool) -> Result<Self, Error> {
+        let sink = match *output {
+            Output::Stderr => Self::stderr(),
+            Output::Stdout => Self::stdout(),
+            Output::File(ref path) => Self::file(path)?,
+            Output::Writer(ref writer) => Self::Writer(writer.clone()),
+            Output::RotatingFile {
+                ref path,
+                max_bytes,
+                max_files,
+            } => Self::Rotating(RotatingFileSink::new(path, max_bytes, max_files)?),
+            Output::TimedRotatingFile { ref path, max_days } => {
+                Self::TimedRotating(TimedRotatingSink::new(path, max_days)?)
+            }
+            #[cfg(all(unix, feature = "unix"))]
+            Output::Syslog {
+                facility,
+                ref socket,
+            } => Self::Syslog {
+                socket: syslog::connect(socket.as_deref())?,
+                facility: facility as u8,
+                severity: syslog::severity(Level::Info),
+                buffer: vec![],
+            },
+            Output::Tcp(addr) => Self::Tcp {
+                stream: None,
+                addr,
+                reconnect_delay: TCP_INITIAL_DELAY,
+                retry_at: None,
+                buffer: vec![],
+                gelf: false,
+            },
+            #[cfg(all(unix, feature = "unix"))]
+            Output::NamedPipe(ref path) => Self::named_pipe(path)?,
+            Output::RingBuffer(ref ring) => Self::RingBuffer {
+                ring: ring.clone(),
+                buffer: vec![],
+            },
+            Output::Udp(addr) => Self::Udp {
+                socket: UdpSocket::bind(if addr.is_ipv4() {
+                    "0.0.0.0:0"
+                } else {
+                    "[::]:0"
+                })?,
+                addr,
+                buffer: vec![],
+                gelf: false,
+            },
+        };
+        match sink {
+            Self::Terminal(_) | Self::Stdout(_) | Self::Ansi(_) | Self::Udp { .. } => Ok(sink),
+            Self::Tcp { .. } | Self::RingBuffer { .. } => Ok(sink),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { .. } => Ok(sink),
+            Self::Fallback(_) | Self::StdoutFallback(_) if env_forces_colors() => {
+                Ok(Self::Ansi(Box::new(sink)))
+            }
+            _ if force_colors => Ok(Self::Ansi(Box::new(sink))),
+            _ => Ok(sink),
+        }
+    }
+
+    /// Wraps the sink into a `BufferedSink` according to the mode.
+    fn buffered(self, mode: BufferMode) -> Self {
+        let capacity = match mode {
+            BufferMode::None => return self,
+            BufferMode::Line => 0,
+            BufferMode::Block(size) => size,
+        };
+        match self {
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { .. } => self,
+            Self::Udp { .. } | Self::Tcp { .. } | Self::RingBuffer { .. } => self,
+            _ => Self::Buffered(BufferedSink {
+                sink: Box::new(self),
+                buffer: Vec::with_capacity(capacity),
+                capacity,
+            }),
+        }
+    }
+
+    #[cfg(feature = "gelf")]
+    /// Switches the network sinks to the GELF framing.
+    pub(crate) fn enable_gelf(&mut self) {
+        match self {
+            Self::Udp { gelf, .. } | Self::Tcp { gelf, .. } => *gelf = true,
+            Self::Tee(m) => m.sinks.iter_mut().for_each(LogSink::enable_gelf),
+            Self::Ansi(s) => s.enable_gelf(),
+            Self::Buffered(b) => b.sink.enable_gelf(),
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    /// Compresses the files of the size based rotating sinks.
+    pub(crate) fn enable_compression(&mut self) {
+        match self {
+            Self::Rotating(r) => r.enable_compression(),
+            Self::Tee(m) => m.sinks.iter_mut().for_each(LogSink::enable_compression),
+            Self::Buffered(b) => b.sink.enable_compression(),
+            _ => {}
+        }
+    }
+
+    /// Creates a sink writing to the stderr terminal, which falls back to
+    /// plain stderr if no terminal can be instantiated.
+    pub fn new_terminal() -> Self {
+        Self::stderr()
+    }
+
+    /// Creates a sink writing to stderr without any coloring.
+    pub fn new_stderr() -> Self {
+        Self::Fallback(std::io::stderr())
+    }
+
+    /// Creates a sink appending to the file, which is created if necessary.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the file cannot be opened.
+    pub fn new_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Self::file(path.as_ref())
+    }
+
+    /// Checks whether the output is written to an interactive terminal.
+    pub(crate) fn is_terminal(&self) -> bool {
+        match self {
+            Self::Terminal(_) => std::io::stderr().is_terminal(),
+            Self::Stdout(_) => std::io::stdout().is_terminal(),
+            #[cfg(feature = "ansi")]
+            Self::Ansi(s) => match **s {
+                Self::Fallback(ref e) => e.is_terminal(),
+                Self::StdoutFallback(ref o) => o.is_terminal(),
+                _ => false,
+            },
+            Self::Tee(m) => m.sinks.iter().any(LogSink::is_terminal),
+            Self::Buffered(b) => b.sink.is_terminal(),
+            _ => false,
+        }
+    }
+
+    /// Checks whether the output is written to stderr or stdout, which are
+    /// the only outputs colored if `MOWL_FORCE_COLOR` is set.
+    pub(crate) fn is_std_stream(&self) -> bool {
+        match self {
+            Self::Terminal(_) | Self::Stdout(_) => true,
+            Self::Fallback(_) | Self::StdoutFallback(_) => true,
+            Self::Ansi(s) => s.is_std_stream(),
+            Self::Tee(m) => m.sinks.iter().any(LogSink::is_std_stream),
+            Self::Buffered(b) => b.sink.is_std_stream(),
+            _ => false,
+        }
+    }
+
+    fn stderr() -> Self {
+        if let Some(term) = term::stderr() {
+            Self::Terminal(term)
+        } else {
+            Self::fallback(Self::Fallback(std::io::stderr()))
+        }
+    }
+
+    fn stdout() -> Self {
+        if let Some(term) = term::stdout() {
+            Self::Stdout(term)
+        } else {
+            Self::fallback(Self::StdoutFallback(std::io::stdout()))
+        }
+    }
+
+    /// The sink if no terminal can be instantiated, for example because the
+    /// terminfo database is missing. With the `ansi` feature it is colored by
+    /// raw ANSI escape sequences instead of not being colored at all.
+    fn fallback(sink: Self) -> Self {
+        if cfg!(feature = "ansi") {
+            Self::Ansi(Box::new(sink))
+        } else {
+            sink
+        }
+    }
+
+    fn file(path: &Path) -> Result<Self, Error> {
+        Ok(Self::File(BufWriter::new(open_append(path)?)))
+    }
+
+    #[cfg(all(unix, feature = "unix"))]
+    fn named_pipe(path: &Path) -> Result<Self, Error> {
+        let pipe = OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|source| Error::OpenFile {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        Ok(Self::NamedPipe(BufWriter::new(pipe)))
+    }
+
+    #[cfg(all(unix, feature = "unix"))]
+    /// Replaces a broken named pipe by stderr, which receives the output that
+    /// could not be written to the pipe. This happens on flushing as well as
+    /// on writing, since records larger than the buffer are written through.
+    fn replace_broken_pipe(&mut self) -> std::io::Result<()> {
+        if let Self::NamedPipe(pipe) = std::mem::replace(self, Self::stderr()) {
+            if let (_, Ok(unwritten)) = pipe.into_parts() {
+                self.write_all(&unwritten)?;
+            }
+        }
+        self.flush()
+    }
+
+    #[cfg(all(unix, feature = "unix"))]
+    /// Announces the level of the record written next.
+    pub(crate) fn start_record(&mut self, level: Level) {
+        match self {
+            Self::Tee(m) => m.sinks.iter_mut().for_each(|s| s.start_record(level)),
+            Self::Ansi(s) => s.start_record(level),
+            Self::Buffered(b) => b.sink.start_record(level),
+            Self::Syslog { severity, .. } => *severity = syslog::severity(level),
+            _ => {}
+        }
+    }
+
+    /// Sets the foreground color. Colors which are not supported by `term`
+    /// are written as raw ANSI escape sequences.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the color cannot be written.
+    pub fn fg<C: Into<ColorSpec>>(&mut self, color: C) -> Result<(), Error> {
+        let color = color.into();
+        match (self, color) {
+            (Self::Terminal(t), ColorSpec::Named(c)) => t.fg(c)?,
+            (Self::Stdout(t), ColorSpec::Named(c)) => t.fg(c)?,
+            (Self::Terminal(t), _) => write!(t, "{}", color.ansi_fg())?,
+            (Self::Stdout(t), _) => write!(t, "{}", color.ansi_fg())?,
+            (Self::Tee(m), _) => m.for_each(|s| s.fg(color)),
+            (Self::Ansi(s), _) => write!(s, "{}", color.ansi_fg())?,
+            (Self::Buffered(b), _) if b.colored() => write!(b, "{}", color.ansi_fg())?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Sets the background color. Colors which are not supported by `term`
+    /// are written as raw ANSI escape sequences.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the color cannot be written.
+    pub fn bg<C: Into<ColorSpec>>(&mut self, color: C) -> Result<(), Error> {
+        let color = color.into();
+        match (self, color) {
+            (Self::Terminal(t), ColorSpec::Named(c)) => t.bg(c)?,
+            (Self::Stdout(t), ColorSpec::Named(c)) => t.bg(c)?,
+            (Self::Terminal(t), _) => write!(t, "{}", color.ansi_bg())?,
+            (Self::Stdout(t), _) => write!(t, "{}", color.ansi_bg())?,
+            (Self::Tee(m), _) => m.for_each(|s| s.bg(color)),
+            (Self::Ansi(s), _) => write!(s, "{}", color.ansi_bg())?,
+            (Self::Buffered(b), _) if b.colored() => write!(b, "{}", color.ansi_bg())?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Sets a text attribute if the terminal supports it.
+    pub(crate) fn attr(&mut self, attr: Attr) -> Result<(), Error> {
+        match self {
+            Self::Terminal(t) if t.supports_attr(attr) => t.attr(attr)?,
+            Self::Stdout(t) if t.supports_attr(attr) => t.attr(attr)?,
+            Self::Tee(m) => m.for_each(|s| s.attr(attr)),
+            Self::Ansi(s) => write!(s, "{}", ansi_attr(attr))?,
+            Self::Buffered(b) if b.colored() => write!(b, "{}", ansi_attr(attr))?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Resets the colors and text attributes.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the reset cannot be written.
+    pub fn reset(&mut self) -> Result<(), Error> {
+        match self {
+            Self::Terminal(t) => t.reset()?,
+            Self::Stdout(t) => t.reset()?,
+            Self::Tee(m) => m.for_each(LogSink::reset),
+            Self::Ansi(s) => write!(s, "\x1b[0m")?,
+            Self::Buffered(b) if b.colored() => write!(b, "\x1b[0m")?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Takes the rendered output of an in-memory sink.
+    pub(crate) fn into_memory(self) -> Vec<u8> {
+        match self {
+            Self::Memory(v) => v,
+            Self::Ansi(s) => s.into_memory(),
+            _ => vec![],
+        }
+    }
+
+    /// Completes a record, which flushes the sink unless it is buffered and
+    /// the buffer is not full yet.
+    pub(crate) fn end_record(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Buffered(b) if b.buffer.len() < b.capacity => Ok(()),
+            Self::Tee(m) => {
+                m.for_each(LogSink::end_record);
+                m.result()
+            }
+            _ => self.flush(),
+        }
+    }
+}
+
+/// The ANSI escape sequence of a text attribute.
+fn ansi_attr(attr: Attr) -> &'static str {
+    match attr {
+        Attr::Bold => "\x1b[1m",
+        Attr::Underline(true) => "\x1b[4m",
+        _ => "",
+    }
+}
+
+/// Implement Write for `LogSink` by forwarding to the underlying Writers
+impl std::io::Write for LogSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Self::Terminal(t) => t.write(buf),
+            Self::Fallback(e) => e.write(buf),
+            Self::Stdout(t) => t.write(buf),
+            Self::StdoutFallback(o) => o.write(buf),
+            Self::File(f) => f.write(buf),
+            Self::Rotating(r) => r.write(buf),
+            Self::Writer(w) => w.lock().write(buf),
+            Self::TimedRotating(r) => r.write(buf),
+            Self::Tee(m) => m.write(buf),
+            Self::Ansi(s) => s.write(buf),
+            Self::Buffered(b) => b.write(buf),
+            Self::Memory(v) => v.write(buf),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { buffer, .. } => buffer.write(buf),
+            Self::Udp { buffer, .. } | Self::Tcp { buffer, .. } => buffer.write(buf),
+            Self::RingBuffer { buffer, .. } => buffer.write(buf),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::NamedPipe(p) => match p.write(buf) {
+                Err(ref e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    self.replace_broken_pipe()?;
+                    self.write(buf)
+                }
+                result => result,
+            },
+        }
+    }
+
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        match self {
+            Self::Terminal(t) => t.write_vectored(bufs),
+            Self::Fallback(e) => e.write_vectored(bufs),
+            Self::Stdout(t) => t.write_vectored(bufs),
+            Self::StdoutFallback(o) => o.write_vectored(bufs),
+            Self::File(f) => f.write_vectored(bufs),
+            Self::Rotating(r) => r.write_vectored(bufs),
+            Self::Writer(w) => w.lock().write_vectored(bufs),
+            Self::TimedRotating(r) => r.write_vectored(bufs),
+            Self::Tee(m) => m.write_vectored(bufs),
+            Self::Ansi(s) => s.write_vectored(bufs),
+            Self::Buffered(b) => b.write_vectored(bufs),
+            Self::Memory(v) => v.write_vectored(bufs),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { buffer, .. } => buffer.write_vectored(bufs),
+            Self::Udp { buffer, .. } | Self::Tcp { buffer, .. } => buffer.write_vectored(bufs),
+            Self::RingBuffer { buffer, .. } => buffer.write_vectored(bufs),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::NamedPipe(p) => match p.write_vectored(bufs) {
+                Err(ref e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    self.replace_broken_pipe()?;
+                    self.write_vectored(bufs)
+                }
+                result => result,
+            },
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Self::Terminal(t) => t.flush(),
+            Self::Fallback(e) => e.flush(),
+            Self::Stdout(t) => t.flush(),
+            Self::StdoutFallback(o) => o.flush(),
+            Self::File(f) => f.flush(),
+            Self::Rotating(r) => r.flush(),
+            Self::Writer(w) => w.lock().flush(),
+            Self::TimedRotating(r) => r.flush(),
+            Self::Tee(m) => m.flush(),
+            Self::Ansi(s) => s.flush(),
+            Self::Buffered(b) => b.flush(),
+            Self::Memory(v) => v.flush(),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog {
+                socket,
+                facility,
+                severity,
+                buffer,
+            } => {
+                if buffer.ends_with(b"\n") {
+                    buffer.pop();
+                }
+                let result = if buffer.is_empty() {
+                    Ok(())
+                } else {
+                    syslog::send(socket, *facility, *severity, buffer)
+                };
+                buffer.clear();
+                result
+            }
+            Self::Udp {
+                socket,
+                addr,
+                buffer,
+                gelf,
+            } => {
+                if *gelf {
+                    if buffer.ends_with(b"\n") {
+                        buffer.pop();
+                    }
+                    for chunk in gelf::chunks(buffer) {
+                        let _ = socket.send_to(&chunk, *addr);
+                    }
+                } else if !buffer.is_empty() {
+                    buffer.truncate(MAX_UDP_PAYLOAD);
+                    // Network errors are discarded, since reporting them
+                    // would produce even more records for the same network
+                    let _ = socket.send_to(buffer, *addr);
+                }
+                buffer.clear();
+                Ok(())
+            }
+            #[cfg(all(unix, feature = "unix"))]
+            Self::NamedPipe(p) => match p.flush() {
+                Err(ref e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    self.replace_broken_pipe()
+                }
+                result => result,
+            },
+            Self::RingBuffer { ring, buffer } => {
+                if buffer.ends_with(b"\n") {
+                    buffer.pop();
+                }
+                if !buffer.is_empty() {
+                    ring.push(String::from_utf8_lossy(buffer).into_owned());
+                }
+                buffer.clear();
+                Ok(())
+            }
+            Self::Tcp {
+                stream,
+                addr,
+                reconnect_delay,
+                retry_at,
+                buffer,
+                gelf,
+            } => {
+                if buffer.is_empty() {
+                    return Ok(());
+                }
+                if *gelf {
+                    if buffer.ends_with(b"\n") {
+                        buffer.pop();
+                    }
+                    buffer.push(b'\0');
+                } else if !buffer.ends_with(b"\n") {
+                    buffer.push(b'\n');
+                }
+                let result = send_tcp(stream, *addr, reconnect_delay, retry_at, buffer);
+                buffer.clear();
+                result
+            }
+        }
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        match self {
+            Self::Terminal(t) => t.write_all(buf),
+            Self::Fallback(e) => e.write_all(buf),
+            Self::Stdout(t) => t.write_all(buf),
+            Self::StdoutFallback(o) => o.write_all(buf),
+            Self::File(f) => f.write_all(buf),
+            Self::Rotating(r) => r.write_all(buf),
+            Self::Writer(w) => w.lock().write_all(buf),
+            Self::TimedRotating(r) => r.write_all(buf),
+            Self::Tee(m) => m.write_all(buf),
+            Self::Ansi(s) => s.write_all(buf),
+            Self::Buffered(b) => b.write_all(buf),
+            Self::Memory(v) => v.write_all(buf),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { buffer, .. } => buffer.write_all(buf),
+            Self::Udp { buffer, .. } | Self::Tcp { buffer, .. } => buffer.write_all(buf),
+            Self::RingBuffer { buffer, .. } => buffer.write_all(buf),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::NamedPipe(p) => match p.write_all(buf) {
+                Err(ref e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    self.replace_broken_pipe()?;
+                    self.write_all(buf)
+                }
+                result => result,
+            },
+        }
+    }
+
+    fn write_fmt(&mut self, args: std::fmt::Arguments<'_>) -> std::io::Result<()> {
+        match self {
+            Self::Terminal(t) => t.write_fmt(args),
+            Self::Fallback(e) => e.write_fmt(args),
+            Self::Stdout(t) => t.write_fmt(args),
+            Self::StdoutFallback(o) => o.write_fmt(args),
+            Self::File(f) => f.write_fmt(args),
+            Self::Rotating(r) => r.write_fmt(args),
+            Self::Writer(w) => w.lock().write_fmt(args),
+            Self::TimedRotating(r) => r.write_fmt(args),
+            Self::Tee(m) => m.write_fmt(args),
+            Self::Ansi(s) => s.write_fmt(args),
+            Self::Buffered(b) => b.write_fmt(args),
+            Self::Memory(v) => v.write_fmt(args),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::Syslog { buffer, .. } => buffer.write_fmt(args),
+            Self::Udp { buffer, .. } | Self::Tcp { buffer, .. } => buffer.write_fmt(args),
+            Self::RingBuffer { buffer, .. } => buffer.write_fmt(args),
+            #[cfg(all(unix, feature = "unix"))]
+            Self::NamedPipe(p) => match p.write_fmt(args) {
+                Err(ref e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                    self.replace_broken_pipe()?;
+                    self.write_fmt(args)
+                }
+                result => result,
+            },
+        }
+    }
+}
+
+/// Sends the buffer via the TCP stream, which gets reconnected if it is not
+/// connected or writing fails. After a failed reconnection, records are
+/// dropped without blocking the caller until the delay has passed, which
+/// doubles with every failed attempt and is reset by a successful write.
+fn send_tcp(
+    stream: &mut Option<TcpStream>,
+    addr: SocketAddr,
+    reconnect_delay: &mut Duration,
+    retry_at: &mut Option<Instant>,
+    buffer: &[u8],
+) -> std::io::Result<()> {
+    if let Some(at) = *retry_at {
+        if Instant::now() < at {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("dropped the record, {} is unreachable", addr),
+            ));
+        }
+    }
+    // A broken connection is reconnected right away, since the peer may
+    // have been restarted
+    if let Some(s) = stream {
+        if s.write_all(buffer).is_ok() {
+            return Ok(());
+        }
+        *stream = None;
+    }
+    let result = TcpStream::connect(addr).and_then(|mut s| {
+        s.write_all(buffer)?;
+        *stream = Some(s);
+        Ok(())
+    });
+    match result {
+        Ok(()) => {
+            *reconnect_delay = TCP_INITIAL_DELAY;
+            *retry_at = None;
+            Ok(())
+        }
+        Err(e) => {
+            *retry_at = Some(Instant::now() + *reconnect_delay);
+            *reconnect_delay = (*reconnect_delay * 2).min(TCP_MAX_DELAY);
+            Err(std::io::Error::new(
+                e.kind(),
+                format!("cannot send the record to {}: {}", addr, e),
+            ))
+        }
+    }
+}
+
+/// Opens the file at the path in append mode, creating it if needed.
+pub(crate) fn open_append(path: &Path) -> Result<File, Error> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() && !parent.is_dir() {
+            return Err(Error::MissingDirectory(parent.to_path_buf()));
+        }
+    }
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .map_err(|source| Error::OpenFile {
+            path: path.to_path_buf(),
+            source,
+        })
+}
+
+/// Checks whether colors are forced by `MOWL_FORCE_COLOR`, even if the output
+/// is not a terminal. `NO_COLOR` takes precedence.
+pub(crate) fn env_forces_colors() -> bool {
+    env::var_os("MOWL_FORCE_COLOR").is_some() && env::var_os("NO_COLOR").is_none()
+}
+
+/// A sink which forwards the output to multiple sinks.
+///
+/// A failing sink does not prevent the other sinks from receiving the output.
+/// All errors of a record are collected and reported together on `flush`.
+pub struct MultiSink {
+    sinks: Vec<LogSink>,
+    errors: Vec<String>,
+}
+
+impl MultiSink {
+    fn for_each<F, E>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut LogSink) -> Result<(), E>,
+        E: std::fmt::Display,
+    {
+        for sink in &mut self.sinks {
+            if let Err(e) = f(sink) {
+                self.errors.push(e.to_string());
+            }
+        }
+    }
+}
+
+impl std::io::Write for MultiSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.for_each(|s| s.write_all(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.for_each(LogSink::flush);
+        self.result()
+    }
+}
+
+impl MultiSink {
+    /// Reports all errors collected since the last call.
+    fn result(&mut self) -> std::io::Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            let errors = self.errors.drain(..).collect::<Vec<_>>();
+            Err(std::io::Error::other(errors.join(", ")))
+        }
+    }
+}
+
+/// A sink which collects the output in memory and writes it to the underlying
+/// sink on flush.
+pub struct BufferedSink {
+    sink: Box<LogSink>,
+    buffer: Vec<u8>,
+    capacity: usize,
+}
+
+impl BufferedSink {
+    /// Checks whether colors of the underlying sink have to be written into
+    /// the buffer.
+    fn colored(&self) -> bool {
+        matches!(
+            *self.sink,
+            LogSink::Terminal(_) | LogSink::Stdout(_) | LogSink::Ansi(_)
+        )
+    }
+}
+
+impl std::io::Write for BufferedSink {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let result = self.sink.write_all(&self.buffer);
+        self.buffer.clear();
+        result?;
+        self.sink.flush()
+    }
+}
+
+impl Drop for BufferedSink {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
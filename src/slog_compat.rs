@@ -0,0 +1,93 @@
+//! Forwarding of `slog` records to a `Logger`
+//!
+//! ```
+//! # #[macro_use] extern crate slog;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! use slog::Drain;
+//!
+//! let logger = mowl::LoggerBuilder::new().build().unwrap();
+//! let root = slog::Logger::root(mowl::slog_compat::MowlDrain::new(logger).fuse(), o!());
+//!
+//! info!(root, "Handled"; "status" => 200);
+//! # }
+//! ```
+use log::{self, Log};
+use slog::{Drain, Key, Level, Never, OwnedKVList, Record, Serializer, KV};
+use std::{
+    fmt::{self, Write},
+    panic::{RefUnwindSafe, UnwindSafe},
+};
+use Logger;
+
+/// A `slog` drain which writes records via a `Logger`.
+///
+/// The key-value pairs of a record are appended to its message like
+/// `key=value`, followed by the ones of the `slog` logger.
+pub struct MowlDrain {
+    logger: Logger,
+}
+
+impl MowlDrain {
+    /// Creates a drain writing via the logger, which also decides which
+    /// records are enabled.
+    pub fn new(logger: Logger) -> Self {
+        Self { logger }
+    }
+}
+
+// The hooks of the logger are only called while logging, and a panic in them
+// cannot leave the drain in an inconsistent state
+impl UnwindSafe for MowlDrain {}
+impl RefUnwindSafe for MowlDrain {}
+
+impl Drain for MowlDrain {
+    type Ok = ();
+    type Err = Never;
+
+    fn log(&self, record: &Record, values: &OwnedKVList) -> Result<(), Never> {
+        let mut message = KvSerializer(record.msg().to_string());
+        // The serializer never fails
+        let _ = record.kv().serialize(record, &mut message);
+        let _ = values.serialize(record, &mut message);
+        self.logger.log(
+            &log::Record::builder()
+                .args(format_args!("{}", message.0))
+                .level(level(record.level()))
+                .target(record.tag())
+                .module_path(Some(record.module()))
+                .file(Some(record.file()))
+                .line(Some(record.line()))
+                .build(),
+        );
+        Ok(())
+    }
+
+    fn is_enabled(&self, level: Level) -> bool {
+        let level = self::level(level);
+        self.logger
+            .enabled(&log::Metadata::builder().level(level).build())
+    }
+}
+
+/// Appends the key-value pairs to the message.
+struct KvSerializer(String);
+
+impl Serializer for KvSerializer {
+    fn emit_arguments(&mut self, key: Key, value: &fmt::Arguments) -> slog::Result {
+        let _ = write!(self.0, " {}={}", key, value);
+        Ok(())
+    }
+}
+
+/// The log level of a slog level.
+fn level(level: Level) -> log::Level {
+    match level {
+        Level::Critical | Level::Error => log::Level::Error,
+        Level::Warning => log::Level::Warn,
+        Level::Info => log::Level::Info,
+        Level::Debug => log::Level::Debug,
+        Level::Trace => log::Level::Trace,
+    }
+}
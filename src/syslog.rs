@@ -0,0 +1,95 @@
+//! Forwarding of log records to the local syslog daemon
+//!
+//! The syslog severities are also the levels of GELF and the priorities of
+//! the journal, so they are available without the `unix` feature.
+#[cfg(all(unix, feature = "unix"))]
+use clock::DateTime;
+#[cfg(all(unix, feature = "unix"))]
+use error::Error;
+use log::Level;
+#[cfg(all(unix, feature = "unix"))]
+use std::{os::unix::net::UnixDatagram, path::Path, process};
+
+#[cfg(all(unix, feature = "unix"))]
+/// The default locations of the syslog socket, tried in order.
+const SOCKETS: [&str; 2] = ["/dev/log", "/var/run/syslog"];
+
+#[cfg(all(unix, feature = "unix"))]
+/// The syslog facility, which describes the kind of program sending the log
+/// records.
+#[allow(missing_docs)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyslogFacility {
+    Kern = 0,
+    User = 1,
+    Mail = 2,
+    Daemon = 3,
+    Auth = 4,
+    Syslog = 5,
+    Lpr = 6,
+    News = 7,
+    Uucp = 8,
+    Cron = 9,
+    AuthPriv = 10,
+    Ftp = 11,
+    Local0 = 16,
+    Local1 = 17,
+    Local2 = 18,
+    Local3 = 19,
+    Local4 = 20,
+    Local5 = 21,
+    Local6 = 22,
+    Local7 = 23,
+}
+
+#[cfg(all(unix, feature = "unix"))]
+/// Connects to the syslog socket, which is either the given path or one of the
+/// default locations.
+pub(crate) fn connect(path: Option<&Path>) -> Result<UnixDatagram, Error> {
+    let socket = UnixDatagram::unbound()?;
+    match path {
+        Some(path) => socket.connect(path)?,
+        None => {
+            let mut result = Ok(());
+            for path in &SOCKETS {
+                result = socket.connect(path);
+                if result.is_ok() {
+                    break;
+                }
+            }
+            result?
+        }
+    }
+    Ok(socket)
+}
+
+/// The syslog severity code of a log level.
+pub(crate) fn severity(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+#[cfg(all(unix, feature = "unix"))]
+/// Sends a single message as `<priority>timestamp mowl[pid]: message`.
+pub(crate) fn send(
+    socket: &UnixDatagram,
+    facility: u8,
+    severity: u8,
+    message: &[u8],
+) -> std::io::Result<()> {
+    let mut datagram = format!(
+        "<{}>{} mowl[{}]: ",
+        u32::from(facility) * 8 + u32::from(severity),
+        DateTime::now().format("%b %e %T"),
+        process::id()
+    )
+    .into_bytes();
+    datagram.extend_from_slice(message);
+    datagram.push(0);
+    socket.send(&datagram)?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! Utilities to assert on log output in tests
+//!
+//! ```
+//! # #[macro_use] extern crate log;
+//! # extern crate mowl;
+//! #
+//! # fn main() {
+//! let logger = mowl::testing::init();
+//! warn!("A warning");
+//!
+//! let records = logger.drain();
+//! assert_eq!(records[0].level, log::Level::Warn);
+//! assert_eq!(records[0].message, "A warning");
+//! # }
+//! ```
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+
+static LOGGER: OnceLock<Arc<MemoryLogger>> = OnceLock::new();
+
+/// Installs a `MemoryLogger` as global logger and returns it.
+///
+/// The logger can only be installed once per process, so subsequent calls
+/// return the already existing instance. The returned logger does not capture
+/// anything if another logger has been set before.
+pub fn init() -> Arc<MemoryLogger> {
+    let mut created = false;
+    let logger = LOGGER.get_or_init(|| {
+        created = true;
+        Arc::new(MemoryLogger::default())
+    });
+    if created && log::set_logger(&**logger).is_ok() {
+        log::set_max_level(LevelFilter::Trace);
+    }
+    logger.clone()
+}
+
+/// A log record captured by the `MemoryLogger`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedRecord {
+    /// The level of the record
+    pub level: Level,
+    /// The module path of the record, or its target if not available
+    pub module: String,
+    /// The formatted message of the record
+    pub message: String,
+}
+
+/// A logger which stores all records in memory instead of writing them.
+#[derive(Debug, Default)]
+pub struct MemoryLogger {
+    records: Arc<Mutex<Vec<CapturedRecord>>>,
+}
+
+impl MemoryLogger {
+    /// Returns a copy of all captured records.
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        self.lock().clone()
+    }
+
+    /// Removes and returns all captured records.
+    pub fn drain(&self) -> Vec<CapturedRecord> {
+        self.lock().drain(..).collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<CapturedRecord>> {
+        self.records.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Log for MemoryLogger {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        self.lock().push(CapturedRecord {
+            level: record.level(),
+            module: record.module_path().unwrap_or(record.target()).to_owned(),
+            message: record.args().to_string(),
+        });
+    }
+
+    fn flush(&self) {}
+}
@@ -0,0 +1,205 @@
+//! Forwarding of `tracing` events to a `Logger`
+//!
+//! ```
+//! # extern crate mowl;
+//! # extern crate tracing;
+//! #
+//! # fn main() {
+//! let logger = mowl::LoggerBuilder::new().build().unwrap();
+//! tracing::subscriber::set_global_default(mowl::tracing_compat::MowlSubscriber::new(logger))
+//!     .unwrap();
+//!
+//! let span = tracing::info_span!("request", id = 42);
+//! let _guard = span.enter();
+//! tracing::info!(status = 200, "Handled");
+//! # }
+//! ```
+use log::{self, Log};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Write},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard, PoisonError,
+    },
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+use Logger;
+
+thread_local! {
+    /// The spans entered on the current thread, innermost last.
+    static STACK: RefCell<Vec<u64>> = const { RefCell::new(vec![]) };
+}
+
+/// A `tracing` subscriber which writes events via a `Logger`.
+///
+/// The fields of an event are appended to its message like `key=value`,
+/// followed by the fields of the entered spans from the outermost to the
+/// innermost one.
+pub struct MowlSubscriber {
+    logger: Logger,
+    next_id: AtomicU64,
+    spans: Mutex<HashMap<u64, SpanData>>,
+}
+
+/// The formatted fields of a span and the number of its handles.
+#[derive(Debug)]
+struct SpanData {
+    fields: String,
+    handles: usize,
+}
+
+impl MowlSubscriber {
+    /// Creates a subscriber writing via the logger, which also decides which
+    /// events are enabled.
+    pub fn new(logger: Logger) -> Self {
+        Self {
+            logger,
+            next_id: AtomicU64::new(1),
+            spans: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn spans(&self) -> MutexGuard<'_, HashMap<u64, SpanData>> {
+        self.spans.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Subscriber for MowlSubscriber {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.logger.enabled(
+            &log::Metadata::builder()
+                .level(level(metadata.level()))
+                .target(metadata.target())
+                .build(),
+        )
+    }
+
+    fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut visitor = FieldVisitor::default();
+        span.record(&mut visitor);
+        self.spans().insert(
+            id,
+            SpanData {
+                fields: visitor.fields,
+                handles: 1,
+            },
+        );
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        if let Some(data) = self.spans().get_mut(&span.into_u64()) {
+            let mut visitor = FieldVisitor {
+                fields: std::mem::take(&mut data.fields),
+                ..Default::default()
+            };
+            values.record(&mut visitor);
+            data.fields = visitor.fields;
+        }
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let mut message = visitor.message;
+        message.push_str(&visitor.fields);
+        {
+            let spans = self.spans();
+            STACK.with(|stack| {
+                for data in stack.borrow().iter().filter_map(|id| spans.get(id)) {
+                    message.push_str(&data.fields);
+                }
+            });
+        }
+        let metadata = event.metadata();
+        self.logger.log(
+            &log::Record::builder()
+                .args(format_args!("{}", message))
+                .level(level(metadata.level()))
+                .target(metadata.target())
+                .module_path(metadata.module_path())
+                .file(metadata.file())
+                .line(metadata.line())
+                .build(),
+        );
+    }
+
+    fn enter(&self, span: &span::Id) {
+        STACK.with(|stack| stack.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &span::Id) {
+        STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            if let Some(position) = stack.iter().rposition(|id| *id == span.into_u64()) {
+                stack.remove(position);
+            }
+        });
+    }
+
+    fn clone_span(&self, span: &span::Id) -> span::Id {
+        if let Some(data) = self.spans().get_mut(&span.into_u64()) {
+            data.handles += 1;
+        }
+        span.clone()
+    }
+
+    fn try_close(&self, span: span::Id) -> bool {
+        let mut spans = self.spans();
+        let closed = match spans.get_mut(&span.into_u64()) {
+            Some(data) => {
+                data.handles -= 1;
+                data.handles == 0
+            }
+            None => false,
+        };
+        if closed {
+            spans.remove(&span.into_u64());
+        }
+        closed
+    }
+}
+
+/// Collects the message and the other fields of an event or span.
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// The log level of a tracing level.
+fn level(level: &Level) -> log::Level {
+    match *level {
+        Level::ERROR => log::Level::Error,
+        Level::WARN => log::Level::Warn,
+        Level::INFO => log::Level::Info,
+        Level::DEBUG => log::Level::Debug,
+        Level::TRACE => log::Level::Trace,
+    }
+}
@@ -0,0 +1,69 @@
+//! Unix specific control of the global logger
+use error::{self, Error};
+use log::{Level, LevelFilter};
+use signal_hook::{
+    consts::{SIGUSR1, SIGUSR2},
+    iterator::Signals,
+};
+use std::{cmp, sync::atomic::Ordering, thread};
+use {GLOBAL_LEVEL, GLOBAL_LOGGER};
+
+/// Changes the level of the global logger via signals: `SIGUSR1` makes it more
+/// verbose and `SIGUSR2` less verbose, cycling through `Error`, `Warn`,
+/// `Info`, `Debug` and `Trace`. Every change is logged at `Info`, even if the
+/// new level is less verbose.
+///
+/// The signals are handled by a background thread. They have no effect if the
+/// global logger has not been registered by mowl.
+///
+/// ```no_run
+/// # extern crate log;
+/// # extern crate mowl;
+/// #
+/// # fn main() {
+/// mowl::init_with_level(log::LevelFilter::Warn).unwrap();
+/// mowl::unix::install_signal_handler().unwrap();
+/// # }
+/// ```
+///
+/// # Errors
+///
+/// An error is returned if the signal handlers cannot be registered.
+pub fn install_signal_handler() -> Result<(), Error> {
+    let mut signals = Signals::new([SIGUSR1, SIGUSR2])?;
+    thread::Builder::new()
+        .name("mowl-signals".to_owned())
+        .spawn(move || {
+            for signal in signals.forever() {
+                let level = match cycle_global_level(signal == SIGUSR1) {
+                    Some(level) => level,
+                    None => continue,
+                };
+                if let Some(logger) = GLOBAL_LOGGER.get() {
+                    let message = format!("Log level changed to {}", level);
+                    if let Err(e) = logger.log_direct(Level::Info, "mowl", &message) {
+                        error::report_failure(Level::Info, "mowl", e);
+                    }
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// Moves the level of the global logger one step towards `Trace` or `Error`,
+/// wrapping around at the ends. Returns the new level or `None` if no logger
+/// has been registered by mowl.
+fn cycle_global_level(more_verbose: bool) -> Option<LevelFilter> {
+    let &(ref level, filters_level) = GLOBAL_LEVEL.get()?;
+    let current = level.load(Ordering::Relaxed);
+    let next = match (more_verbose, current) {
+        (true, n) if n >= LevelFilter::Trace as usize => 1,
+        (true, n) => n + 1,
+        (false, n) if n <= 1 => LevelFilter::Trace as usize,
+        (false, n) => n - 1,
+    };
+    level.store(next, Ordering::Relaxed);
+    let next = LevelFilter::iter().nth(next)?;
+    log::set_max_level(cmp::max(next, filters_level));
+    Some(next)
+}
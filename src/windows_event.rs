@@ -0,0 +1,110 @@
+//! Logging to the Windows Application Event Log
+use error::{self, Error};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::{ffi::OsStr, io, iter, os::windows::ffi::OsStrExt, ptr};
+use windows_sys::Win32::{
+    Foundation::HANDLE,
+    System::EventLog::{
+        DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+        EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, REPORT_EVENT_TYPE,
+    },
+};
+
+/// Installs a `WindowsEventLogger` for the event source as global logger.
+///
+/// # Errors
+///
+/// An error is returned if the event source cannot be registered or if a
+/// logger has already been set.
+pub fn init_windows_event(source: &str, level: LevelFilter) -> Result<(), Error> {
+    let logger = WindowsEventLogger::new(source, level)?;
+    ::set_boxed_logger(Box::new(logger), level)
+}
+
+/// A logger which reports each record as event to the Application Event Log.
+///
+/// Records of level `Error` and `Warn` are reported as error and warning
+/// events, all lower levels as information events.
+#[derive(Debug)]
+pub struct WindowsEventLogger {
+    level: LevelFilter,
+    handle: HANDLE,
+}
+
+impl WindowsEventLogger {
+    /// Registers the event source, like the name of the application.
+    ///
+    /// # Errors
+    ///
+    /// An error is returned if the event source cannot be registered.
+    pub fn new(source: &str, level: LevelFilter) -> Result<Self, Error> {
+        let source = wide(source);
+        // SAFETY: The source is a null terminated wide string
+        let handle = unsafe { RegisterEventSourceW(ptr::null(), source.as_ptr()) };
+        if handle == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(Self { level, handle })
+    }
+
+    fn report(&self, record: &Record) -> Result<(), Error> {
+        let message = wide(&record.args().to_string());
+        let strings = [message.as_ptr()];
+        // SAFETY: The handle is valid until the logger is dropped and the
+        // single string is a null terminated wide string
+        let reported = unsafe {
+            ReportEventW(
+                self.handle,
+                event_type(record.level()),
+                0,
+                0,
+                ptr::null_mut(),
+                1,
+                0,
+                strings.as_ptr(),
+                ptr::null(),
+            )
+        };
+        if reported == 0 {
+            return Err(io::Error::last_os_error().into());
+        }
+        Ok(())
+    }
+}
+
+impl Drop for WindowsEventLogger {
+    fn drop(&mut self) {
+        // SAFETY: The handle has been registered by `new`
+        unsafe { DeregisterEventSource(self.handle) };
+    }
+}
+
+impl Log for WindowsEventLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            if let Err(e) = self.report(record) {
+                error::report_failure(record.level(), record.module_path().unwrap_or("?"), e);
+            }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// The event type of a log level.
+fn event_type(level: Level) -> REPORT_EVENT_TYPE {
+    match level {
+        Level::Error => EVENTLOG_ERROR_TYPE,
+        Level::Warn => EVENTLOG_WARNING_TYPE,
+        Level::Info | Level::Debug | Level::Trace => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+/// Converts the string into a null terminated wide string.
+fn wide(s: &str) -> Vec<u16> {
+    OsStr::new(s).encode_wide().chain(iter::once(0)).collect()
+}
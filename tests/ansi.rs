@@ -0,0 +1,37 @@
+#![cfg(feature = "ansi")]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Log, Record};
+use std::{env, process::Command};
+
+#[test]
+fn ansi_fallback() {
+    if env::var_os("MOWL_ANSI_CHILD").is_some() {
+        let logger = mowl::LoggerBuilder::new()
+            .timestamp(false)
+            .color_mode(mowl::ColorMode::Always)
+            .build()
+            .unwrap();
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Without terminfo"))
+                .level(Level::Warn)
+                .module_path(Some("ansi"))
+                .build(),
+        );
+        return;
+    }
+
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "ansi_fallback", "--nocapture"])
+        .env("MOWL_ANSI_CHILD", "1")
+        .env("TERM", "mowl-unknown")
+        .env_remove("TERMINFO")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("\x1b[94m[ansi]\x1b[0m \x1b[93m\x1b[1m[WARN]\x1b[0m Without terminfo\n")
+    );
+}
@@ -0,0 +1,44 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::Level;
+use std::{env, fs, panic, process, thread};
+
+#[test]
+fn context() {
+    let path = env::temp_dir().join(format!("mowl-context-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let log = |message| logger.log_message(Level::Info, "context", message).unwrap();
+
+    mowl::context::insert("user", "alice");
+    mowl::context::insert("request", "42");
+    log("Both");
+    thread::scope(|s| {
+        s.spawn(|| log("Other thread"));
+    });
+    mowl::context::remove("request");
+    mowl::context::scope("user", "bob", || log("Scoped"));
+    log("Restored");
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+        mowl::context::scope("session", "1", || panic!("Unwound"))
+    }));
+    assert!(result.is_err());
+    mowl::context::remove("user");
+    log("Empty");
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[context] [INFO] Both request=42 user=alice\n\
+         [context] [INFO] Other thread\n\
+         [context] [INFO] Scoped user=bob\n\
+         [context] [INFO] Restored user=alice\n\
+         [context] [INFO] Empty\n"
+    );
+}
@@ -0,0 +1,20 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, LevelFilter};
+use std::env;
+
+#[test]
+fn init_from_env() {
+    env::set_var("MOWL_LOG", "warn,env=debug");
+    env::set_var("RUST_LOG", "trace");
+    mowl::init_from_env().unwrap();
+
+    assert_eq!(log::max_level(), LevelFilter::Debug);
+    assert!(log_enabled!(target: "env", Level::Debug));
+    assert!(!log_enabled!(target: "other", Level::Info));
+    assert!(log_enabled!(target: "other", Level::Warn));
+}
@@ -0,0 +1,68 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Log, Record};
+use std::{env, fs, process, process::Command};
+
+fn record(logger: &mowl::Logger) {
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Forced"))
+            .level(Level::Warn)
+            .module_path(Some("force"))
+            .build(),
+    );
+    logger.flush();
+}
+
+fn log_to_file(name: &str) -> String {
+    let path = env::temp_dir().join(format!("mowl-{}-{}.log", name, process::id()));
+    let _ = fs::remove_file(&path);
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    record(&logger);
+    fs::read_to_string(&path).unwrap()
+}
+
+fn log_to_stderr(no_color: bool) -> String {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["--exact", "force_color_stderr", "--nocapture"])
+        .env("MOWL_FORCE_COLOR_CHILD", "1")
+        .env("MOWL_FORCE_COLOR", "1")
+        .env("TERM", "xterm")
+        .env_remove("NO_COLOR");
+    if no_color {
+        command.env("NO_COLOR", "1");
+    }
+    String::from_utf8(command.output().unwrap().stderr).unwrap()
+}
+
+#[test]
+fn force_color() {
+    env::remove_var("NO_COLOR");
+    env::set_var("TERM", "xterm");
+    assert_eq!(log_to_file("force-color-unset"), "[force] [WARN] Forced\n");
+
+    // Files stay plain, since only stderr and stdout are forced
+    env::set_var("MOWL_FORCE_COLOR", "1");
+    assert_eq!(log_to_file("force-color"), "[force] [WARN] Forced\n");
+}
+
+#[test]
+fn force_color_stderr() {
+    if env::var_os("MOWL_FORCE_COLOR_CHILD").is_some() {
+        let logger = mowl::LoggerBuilder::new().timestamp(false).build().unwrap();
+        record(&logger);
+        return;
+    }
+
+    // The stderr of the child is a pipe, which is colored nevertheless
+    assert!(log_to_stderr(false).contains("\x1b[1m[WARN]"));
+    assert!(log_to_stderr(true).contains("[force] [WARN] Forced\n"));
+}
@@ -0,0 +1,18 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::LevelFilter;
+
+#[test]
+fn init_or_default() {
+    mowl::init_or_default(LevelFilter::Warn);
+    mowl::init_or_default(LevelFilter::Trace);
+
+    assert_eq!(log::max_level(), LevelFilter::Warn);
+    assert_eq!(
+        mowl::init().unwrap_err().to_string(),
+        "logger already initialized"
+    );
+}
@@ -0,0 +1,33 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::LevelFilter;
+use std::{collections::BTreeMap, env, fs, process};
+
+#[test]
+fn init_with_config() {
+    let path = env::temp_dir().join(format!("mowl-init-with-config-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    let mut module_filters = BTreeMap::new();
+    module_filters.insert(module_path!().to_owned(), LevelFilter::Debug);
+    mowl::init_with_config(mowl::LoggerConfig {
+        level: LevelFilter::Warn,
+        timestamp_mode: mowl::TimestampMode::Off,
+        module_filters,
+        file: Some(path.clone()),
+        ..Default::default()
+    })
+    .unwrap();
+    debug!("Configured");
+    trace!("Filtered");
+    log::logger().flush();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[init_with_config] [DEBUG] Configured\n"
+    );
+    assert!(mowl::init_with_config(mowl::LoggerConfig::default()).is_err());
+}
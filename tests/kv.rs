@@ -0,0 +1,23 @@
+#![cfg(feature = "kv")]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use std::{env, fs, process};
+
+#[test]
+fn key_values() {
+    let path = env::temp_dir().join(format!("mowl-kv-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .init()
+        .unwrap();
+
+    info!(target: "test", k1 = "v1", k2 = 42; "msg");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.contains("msg k1=v1 k2=42"));
+}
@@ -0,0 +1,54 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+//! The locale is read from the environment, which is shared by all tests of a
+//! binary, so the symbol fallback is tested separately.
+extern crate log;
+extern crate mowl;
+
+use log::Level;
+use std::{env, fs};
+
+fn log_symbol(name: &str, mode: mowl::ColorMode, disable_colors: bool) -> String {
+    let path = env::temp_dir().join(format!("mowl-level-symbol-{}.log", name));
+    let _ = fs::remove_file(&path);
+    let mut logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mode)
+        .level_format(mowl::LevelStyle::Symbol)
+        .build()
+        .unwrap();
+    if disable_colors {
+        logger.disable_colors();
+    }
+    logger.log_message(Level::Warn, "lib", "Styled").unwrap();
+    fs::read_to_string(&path).unwrap()
+}
+
+#[test]
+fn level_symbol() {
+    env::set_var("LC_ALL", "en_US.UTF-8");
+    assert_eq!(
+        log_symbol("utf8", mowl::ColorMode::Never, false),
+        "[lib] ⚠ Styled\n"
+    );
+
+    env::set_var("LC_ALL", "C");
+    assert_eq!(
+        log_symbol("colored", mowl::ColorMode::Always, false),
+        "\x1b[94m[lib]\x1b[0m \x1b[93m\x1b[1m⚠\x1b[0m Styled\n"
+    );
+    assert_eq!(
+        log_symbol("never", mowl::ColorMode::Never, false),
+        "[lib] [W] Styled\n"
+    );
+    // Files are not colored automatically
+    assert_eq!(
+        log_symbol("auto", mowl::ColorMode::Auto, false),
+        "[lib] [W] Styled\n"
+    );
+    assert_eq!(
+        log_symbol("disabled", mowl::ColorMode::Always, true),
+        "[lib] [W] Styled\n"
+    );
+}
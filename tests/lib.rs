@@ -1,8 +1,46 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+#[cfg(feature = "cbor")]
+extern crate ciborium;
+#[cfg(feature = "gzip")]
+extern crate flate2;
 extern crate mowl;
 
 #[macro_use]
 extern crate log;
+#[cfg(any(feature = "json", feature = "gelf"))]
+extern crate serde_json;
 extern crate term;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+#[cfg(feature = "gzip")]
+use std::io::Read;
+use std::{
+    env, fs,
+    path::PathBuf,
+    process,
+    sync::Arc,
+    thread,
+    time::{Duration, SystemTime},
+};
+
+fn log_file(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("mowl-{}-{}.log", name, process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn log_message(logger: &mowl::Logger, level: Level, message: &str) {
+    logger.log(
+        &Record::builder()
+            .args(format_args!("{}", message))
+            .level(level)
+            .module_path(Some("lib"))
+            .build(),
+    );
+}
 
 #[test]
 fn success_log() {
@@ -13,3 +51,1993 @@ fn success_log() {
     trace!("Trace");
     error!("Error");
 }
+
+#[test]
+fn builder_level() {
+    let logger = mowl::LoggerBuilder::new()
+        .level(LevelFilter::Warn)
+        .colors(false)
+        .build()
+        .unwrap();
+    assert!(logger.enabled(&Metadata::builder().level(Level::Error).build()));
+    assert!(logger.enabled(&Metadata::builder().level(Level::Warn).build()));
+    assert!(!logger.enabled(&Metadata::builder().level(Level::Info).build()));
+}
+
+#[test]
+fn file_log() {
+    let path = log_file("file-log");
+    let logger = mowl::LoggerBuilder::new().file(&path).build().unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Into the file"))
+            .level(Level::Info)
+            .module_path(Some("lib"))
+            .build(),
+    );
+    logger.flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with("[lib] [INFO] Into the file\n"));
+    assert!(!content.contains('\x1b'));
+}
+
+#[test]
+fn file_log_missing_parent_dir() {
+    let path = log_file("missing").join("mowl.log");
+    match mowl::LoggerBuilder::new().file(path).build() {
+        Err(mowl::Error::MissingDirectory(_)) => {}
+        _ => panic!("Expected a missing directory error"),
+    }
+}
+
+#[test]
+fn timestamp_format() {
+    let path = log_file("timestamp-format");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp_format("%H:%M:%S")
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Time only"))
+            .level(Level::Info)
+            .module_path(Some("lib"))
+            .build(),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    let (timestamp, rest) = content.split_at(11);
+    assert_eq!(timestamp.len(), 11);
+    assert!(timestamp.starts_with('[') && timestamp.ends_with("] "));
+    assert_eq!(timestamp.matches(':').count(), 2);
+    assert_eq!(rest, "[lib] [INFO] Time only\n");
+}
+
+#[test]
+fn timestamp_format_invalid() {
+    match mowl::LoggerBuilder::new().timestamp_format("%Q").build() {
+        Err(mowl::Error::TimestampFormat { format, .. }) => assert_eq!(format, "%Q"),
+        _ => panic!("Expected a timestamp format error"),
+    }
+}
+
+#[test]
+fn level_color() {
+    assert!(mowl::LoggerBuilder::new()
+        .level_color(Level::Warn, term::color::MAGENTA)
+        .build()
+        .is_ok());
+    match mowl::LoggerBuilder::new()
+        .level_color(Level::Error, 256)
+        .build()
+    {
+        Err(mowl::Error::ColorOutOfRange(256)) => {}
+        _ => panic!("Expected a color out of range error"),
+    }
+}
+
+#[test]
+fn output_stdout() {
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Stdout)
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("To stdout"))
+            .level(Level::Info)
+            .build(),
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_format() {
+    let path = log_file("json-format");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .format(mowl::Format::Json)
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("A \"quoted\" message"))
+            .level(Level::Warn)
+            .module_path(Some("lib"))
+            .build(),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 1);
+    let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert!(value["timestamp"].is_string());
+    assert_eq!(value["level"], "WARN");
+    assert_eq!(value["module"], "lib");
+    assert_eq!(value["message"], "A \"quoted\" message");
+}
+
+#[cfg(all(feature = "json", feature = "kv"))]
+#[test]
+fn json_format_key_values() {
+    let path = log_file("json-format-key-values");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .format(mowl::Format::Json)
+        .build()
+        .unwrap();
+    let key_values: &[(&str, log::kv::Value)] = &[("k1", "v1".into()), ("k2", 42.into())];
+    logger.log(
+        &Record::builder()
+            .args(format_args!("msg"))
+            .level(Level::Info)
+            .module_path(Some("lib"))
+            .key_values(&key_values)
+            .build(),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(value["message"], "msg");
+    assert_eq!(value["k1"], "v1");
+    assert_eq!(value["k2"], 42);
+}
+
+#[test]
+fn module_filter() {
+    let logger = mowl::LoggerBuilder::new()
+        .level(LevelFilter::Info)
+        .module_filter("hyper", LevelFilter::Warn)
+        .module_filter("hyper::server", LevelFilter::Trace)
+        .build()
+        .unwrap();
+    let enabled =
+        |target, level| logger.enabled(&Metadata::builder().target(target).level(level).build());
+    assert!(!enabled("hyper::client", Level::Debug));
+    assert!(enabled("hyper::client", Level::Warn));
+    assert!(!enabled("hyper", Level::Info));
+    assert!(enabled("hyper::server::conn", Level::Trace));
+    assert!(enabled("hyperx", Level::Info));
+    assert!(!enabled("myapp", Level::Debug));
+}
+
+#[test]
+fn parse_directives() {
+    assert_eq!(mowl::parse_directives(""), Ok(vec![]));
+    assert_eq!(
+        mowl::parse_directives("info"),
+        Ok(vec![(None, LevelFilter::Info)])
+    );
+    assert_eq!(
+        mowl::parse_directives("WARN, hyper=error,myapp ,,"),
+        Ok(vec![
+            (None, LevelFilter::Warn),
+            (Some("hyper".to_owned()), LevelFilter::Error),
+            (Some("myapp".to_owned()), LevelFilter::Trace),
+        ])
+    );
+    assert_eq!(
+        mowl::parse_directives("myapp::db=off/foo"),
+        Ok(vec![(Some("myapp::db".to_owned()), LevelFilter::Off)])
+    );
+}
+
+#[test]
+fn parse_directives_invalid() {
+    let error = mowl::parse_directives("warn,x=none").unwrap_err();
+    assert_eq!(error.directive, "x=none");
+    assert_eq!(
+        error.to_string(),
+        "invalid directive 'x=none': invalid level"
+    );
+    assert_eq!(
+        mowl::parse_directives("=debug").unwrap_err().reason,
+        "empty module name"
+    );
+    assert!(mowl::parse_directives("info/a/b").is_err());
+    match mowl::init_with_directives("info,=debug") {
+        Err(mowl::Error::Directive(_)) => {}
+        _ => panic!("Expected a directive error"),
+    }
+}
+
+#[test]
+fn add_sink() {
+    let first = log_file("add-sink-first");
+    let second = log_file("add-sink-second");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&first)
+        .add_sink(mowl::Output::File(second.clone()))
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Into both files"))
+            .level(Level::Error)
+            .module_path(Some("lib"))
+            .build(),
+    );
+
+    for path in &[first, second] {
+        let content = fs::read_to_string(path).unwrap();
+        assert!(content.ends_with("[lib] [ERROR] Into both files\n"));
+    }
+}
+
+#[test]
+fn thread_id() {
+    let path = log_file("thread-id");
+    let logger = Arc::new(
+        mowl::LoggerBuilder::new()
+            .file(&path)
+            .thread_id(true)
+            .build()
+            .unwrap(),
+    );
+    let threads = (0..4)
+        .map(|_| {
+            let logger = logger.clone();
+            thread::spawn(move || {
+                logger.log(
+                    &Record::builder()
+                        .args(format_args!("From a thread"))
+                        .level(Level::Info)
+                        .module_path(Some("lib"))
+                        .build(),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    let mut ids = content
+        .lines()
+        .map(|l| {
+            assert!(l.ends_with("] [INFO] From a thread"));
+            let start = l.find("[lib] [thread-").unwrap() + 14;
+            let len = l[start..].find(']').unwrap();
+            l[start..start + len].parse::<u64>().unwrap()
+        })
+        .collect::<Vec<_>>();
+    ids.sort_unstable();
+    ids.dedup();
+    assert_eq!(ids.len(), 4);
+}
+
+#[test]
+fn thread_name() {
+    let path = log_file("thread-name");
+    let logger = Arc::new(
+        mowl::LoggerBuilder::new()
+            .file(&path)
+            .colors(false)
+            .timestamp(false)
+            .thread_name(true)
+            .build()
+            .unwrap(),
+    );
+    let named = logger.clone();
+    thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(move || log_message(&named, Level::Info, "Named"))
+        .unwrap()
+        .join()
+        .unwrap();
+    let anonymous = logger.clone();
+    thread::spawn(move || log_message(&anonymous, Level::Info, "Anonymous"))
+        .join()
+        .unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[lib] [worker] [INFO] Named\n[lib] [<anonymous>] [INFO] Anonymous\n"
+    );
+}
+
+#[test]
+fn thread_name_and_id() {
+    let path = log_file("thread-name-and-id");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .thread_name(true)
+        .thread_id(true)
+        .build()
+        .unwrap();
+    thread::Builder::new()
+        .name("worker".to_owned())
+        .spawn(move || log_message(&logger, Level::Info, "Combined"))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let field = content.split(' ').nth(1).unwrap();
+    assert!(field.starts_with("[worker/") && field.ends_with(']'));
+    assert!(field[8..field.len() - 1].parse::<u64>().is_ok());
+}
+
+#[test]
+fn location() {
+    let path = log_file("location");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .location(true)
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("With location"))
+            .level(Level::Info)
+            .file(Some("src/main.rs"))
+            .line(Some(42))
+            .build(),
+    );
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Without location"))
+            .level(Level::Info)
+            .build(),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines = content.lines().collect::<Vec<_>>();
+    assert!(lines[0].ends_with("[INFO] With location (src/main.rs:42)"));
+    assert!(lines[1].ends_with("[INFO] Without location"));
+}
+
+#[test]
+fn set_level() {
+    let logger = mowl::LoggerBuilder::new()
+        .level(LevelFilter::Warn)
+        .build()
+        .unwrap();
+    let info = Metadata::builder().level(Level::Info).build();
+    assert!(!logger.enabled(&info));
+    logger.set_level(LevelFilter::Info);
+    assert!(logger.enabled(&info));
+    logger.set_level(LevelFilter::Off);
+    assert!(!logger.enabled(&Metadata::builder().level(Level::Error).build()));
+}
+
+#[test]
+fn logfmt_format() {
+    let path = log_file("logfmt-format");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .format(mowl::Format::Logfmt)
+        .build()
+        .unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Say \"hello\"\nworld"))
+            .level(Level::Debug)
+            .module_path(Some("lib::fmt"))
+            .build(),
+    );
+
+    let content = fs::read_to_string(&path).unwrap();
+    let (time, rest) = content.split_at(content.find(' ').unwrap());
+    assert!(time.starts_with("time="));
+    assert_eq!(
+        rest.as_bytes(),
+        &b" level=DEBUG module=lib::fmt msg=\"Say \\\"hello\\\"\\nworld\"\n"[..]
+    );
+}
+
+#[test]
+fn timestamp_mode_elapsed() {
+    let path = log_file("timestamp-mode-elapsed");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp_mode(mowl::TimestampMode::Elapsed)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Elapsed");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let (timestamp, rest) = content.split_at(content.find("] ").unwrap() + 2);
+    assert!(timestamp.starts_with("[+") && timestamp.ends_with("s] "));
+    let seconds = &timestamp[2..timestamp.len() - 3];
+    let (whole, millis) = seconds.split_at(seconds.find('.').unwrap());
+    assert!(whole.parse::<u64>().is_ok());
+    assert_eq!(millis.len(), 4);
+    assert_eq!(rest, "[lib] [INFO] Elapsed\n");
+}
+
+#[test]
+fn timestamp_disabled() {
+    let path = log_file("timestamp-disabled");
+    for format in &[mowl::Format::Text, mowl::Format::Logfmt] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .format(format.clone())
+            .timestamp(false)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Info, "No time");
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[lib] [INFO] No time\nlevel=INFO module=lib msg=\"No time\"\n"
+    );
+}
+
+#[test]
+fn level_style() {
+    let path = log_file("level-style");
+    for style in &[mowl::LevelStyle::Full, mowl::LevelStyle::Short] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .level_format(*style)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Warn, "Styled");
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [WARN] Styled\n[lib] [W] Styled\n");
+}
+
+#[test]
+fn field_delimiter_and_separator() {
+    let path = log_file("field-delimiter");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .field_delimiter('{', '}')
+        .field_separator(" | ")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Delimited");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "{lib} | {INFO} | Delimited\n");
+}
+
+#[test]
+fn color_mode() {
+    let path = log_file("color-mode");
+    for mode in &[
+        mowl::ColorMode::Always,
+        mowl::ColorMode::Auto,
+        mowl::ColorMode::Never,
+    ] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .color_mode(*mode)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Info, "Colored");
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "\x1b[94m[lib]\x1b[0m \x1b[92m\x1b[1m[INFO]\x1b[0m Colored\n\
+         [lib] [INFO] Colored\n\
+         [lib] [INFO] Colored\n"
+    );
+}
+
+#[test]
+fn color_256() {
+    env::set_var("COLORTERM", "truecolor");
+    let path = log_file("color-256");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .bold_level(false)
+        .level_color(Level::Info, mowl::ColorSpec::Color256(208))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Orange");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "\x1b[94m[lib]\x1b[0m \x1b[38;5;208m[INFO]\x1b[0m Orange\n"
+    );
+}
+
+#[cfg(feature = "truecolor")]
+#[test]
+fn color_rgb() {
+    env::set_var("COLORTERM", "truecolor");
+    let path = log_file("color-rgb");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .bold_level(false)
+        .level_color(Level::Warn, mowl::ColorSpec::Rgb(255, 128, 0))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "Orange");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "\x1b[94m[lib]\x1b[0m \x1b[38;2;255;128;0m[WARN]\x1b[0m Orange\n"
+    );
+}
+
+#[test]
+fn level_bg_color() {
+    let path = log_file("level-bg-color");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .bold_level(false)
+        .underline_errors(false)
+        .level_bg_color(Level::Error, term::color::RED)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Error, "Alert");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "\x1b[94m[lib]\x1b[0m \x1b[91m\x1b[41m[ERROR]\x1b[0m Alert\n"
+    );
+}
+
+#[test]
+fn level_attributes() {
+    let path = log_file("level-attributes");
+    for (bold, underline) in [(true, true), (false, true), (true, false)] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .color_mode(mowl::ColorMode::Always)
+            .bold_level(bold)
+            .underline_errors(underline)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Error, "Attributed");
+        log_message(&logger, Level::Warn, "Warned");
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "\x1b[94m[lib]\x1b[0m \x1b[91m\x1b[1m[ERROR]\x1b[0m \x1b[4mAttributed\x1b[0m\n\
+         \x1b[94m[lib]\x1b[0m \x1b[93m\x1b[1m[WARN]\x1b[0m Warned\n\
+         \x1b[94m[lib]\x1b[0m \x1b[91m[ERROR]\x1b[0m \x1b[4mAttributed\x1b[0m\n\
+         \x1b[94m[lib]\x1b[0m \x1b[93m[WARN]\x1b[0m Warned\n\
+         \x1b[94m[lib]\x1b[0m \x1b[91m\x1b[1m[ERROR]\x1b[0m Attributed\n\
+         \x1b[94m[lib]\x1b[0m \x1b[93m\x1b[1m[WARN]\x1b[0m Warned\n"
+    );
+}
+
+#[test]
+fn rate_limit() {
+    let path = log_file("rate-limit");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .rate_limit(Level::Warn, 2)
+        .build()
+        .unwrap();
+    for i in 0..5 {
+        log_message(&logger, Level::Warn, &format!("Spam {}", i));
+        log_message(&logger, Level::Info, &format!("Info {}", i));
+    }
+    thread::sleep(std::time::Duration::from_millis(1100));
+    log_message(&logger, Level::Warn, "Again");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let warnings: Vec<_> = content.lines().filter(|l| l.contains("[WARN]")).collect();
+    assert_eq!(
+        warnings,
+        [
+            "[lib] [WARN] Spam 0",
+            "[lib] [WARN] Spam 1",
+            "[lib] [WARN] [3 messages suppressed]",
+            "[lib] [WARN] Again",
+        ]
+    );
+    assert_eq!(content.matches("[INFO]").count(), 5);
+}
+
+#[test]
+fn rate_limit_zero() {
+    let path = log_file("rate-limit-zero");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .rate_limit(Level::Warn, 0)
+        .build()
+        .unwrap();
+    for i in 0..3 {
+        log_message(&logger, Level::Warn, &format!("Spam {}", i));
+    }
+    thread::sleep(std::time::Duration::from_millis(1100));
+    log_message(&logger, Level::Warn, "Dropped");
+    log_message(&logger, Level::Warn, "Dropped");
+    thread::sleep(std::time::Duration::from_millis(1100));
+    log_message(&logger, Level::Warn, "Dropped");
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[lib] [WARN] [3 messages suppressed]\n\
+         [lib] [WARN] [2 messages suppressed]\n"
+    );
+}
+
+#[test]
+fn suppress_duplicates() {
+    let path = log_file("suppress-duplicates");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .suppress_duplicates(true)
+        .build()
+        .unwrap();
+    for _ in 0..100 {
+        log_message(&logger, Level::Warn, "Same");
+    }
+    logger.flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[lib] [WARN] Same\n[lib] [WARN] [repeated 99 times]\n"
+    );
+
+    log_message(&logger, Level::Warn, "Same");
+    log_message(&logger, Level::Warn, "Same");
+    log_message(&logger, Level::Info, "Other");
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with("[lib] [WARN] [repeated 2 times]\n[lib] [INFO] Other\n"));
+}
+
+#[cfg(all(unix, feature = "unix"))]
+#[test]
+fn syslog() {
+    let path = log_file("syslog");
+    let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Syslog {
+            facility: mowl::SyslogFacility::Local0,
+            socket: Some(path.clone()),
+        })
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "To syslog");
+
+    let mut buf = [0; 256];
+    let len = socket.recv(&mut buf).unwrap();
+    let datagram = String::from_utf8_lossy(&buf[..len]);
+    assert!(datagram.starts_with("<132>"));
+    assert!(datagram.ends_with(&format!(
+        "mowl[{}]: [lib] [WARN] To syslog\0",
+        process::id()
+    )));
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(unix, feature = "journald"))]
+#[test]
+fn journald() {
+    let path = log_file("journald");
+    let socket = std::os::unix::net::UnixDatagram::bind(&path).unwrap();
+    let logger = mowl::journald::JournaldLogger::with_socket(&path, LevelFilter::Info).unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("First\nSecond"))
+            .level(Level::Error)
+            .file(Some("lib.rs"))
+            .line(Some(42))
+            .build(),
+    );
+
+    let mut buf = [0; 256];
+    let len = socket.recv(&mut buf).unwrap();
+    let mut expected = b"PRIORITY=3\nMESSAGE\n".to_vec();
+    expected.extend_from_slice(&12u64.to_le_bytes());
+    expected.extend_from_slice(b"First\nSecond\nSYSLOG_IDENTIFIER=");
+    assert!(buf[..len].starts_with(&expected));
+    assert!(buf[..len].ends_with(b"\nCODE_FILE=lib.rs\nCODE_LINE=42\n"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(feature = "serde", feature = "json"))]
+#[test]
+fn logger_config() {
+    let path = log_file("logger-config");
+    let config: mowl::LoggerConfig = serde_json::from_value(serde_json::json!({
+        "level": "warn",
+        "color_mode": "never",
+        "timestamp_mode": "off",
+        "module_filters": {"lib": "debug"},
+        "file": path,
+    }))
+    .unwrap();
+    assert_eq!(config.level, LevelFilter::Warn);
+    assert_eq!(config.module_filters["lib"], LevelFilter::Debug);
+    assert_eq!(config.color_mode, mowl::ColorMode::Never);
+    assert!(!config.thread_id);
+
+    let logger = mowl::LoggerBuilder::from(config.clone()).build().unwrap();
+    log_message(&logger, Level::Info, "Filtered");
+    log_message(&logger, Level::Warn, "Configured");
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [WARN] Configured\n");
+
+    let value = serde_json::to_value(&config).unwrap();
+    assert_eq!(value["level"], "WARN");
+    assert_eq!(value["timestamp_mode"], "off");
+    assert_eq!(
+        serde_json::from_value::<mowl::LoggerConfig>(value).unwrap(),
+        config
+    );
+}
+
+#[test]
+fn buffer_mode() {
+    let path = log_file("buffer-mode-line");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .buffer_mode(mowl::BufferMode::Line)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Line");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "[lib] [INFO] Line\n");
+
+    let path = log_file("buffer-mode-block");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .buffer_mode(mowl::BufferMode::Block(40))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "First");
+    log_message(&logger, Level::Info, "Second");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+    log_message(&logger, Level::Info, "Third");
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 3);
+    log_message(&logger, Level::Info, "Fourth");
+    logger.flush();
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 4);
+    log_message(&logger, Level::Info, "Fifth");
+    drop(logger);
+    assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 5);
+}
+
+#[test]
+fn async_dispatch() {
+    let path = log_file("async-dispatch");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .async_dispatch(4, mowl::async_logger::OverflowPolicy::Block)
+        .build()
+        .unwrap();
+    for i in 0..100 {
+        log_message(&logger, Level::Info, &format!("Async {}", i));
+    }
+    logger.flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let expected: String = (0..100)
+        .map(|i| format!("[lib] [INFO] Async {}\n", i))
+        .collect();
+    assert_eq!(content, expected);
+}
+
+#[test]
+fn async_dispatch_drop() {
+    let path = log_file("async-dispatch-drop");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .async_dispatch(1, mowl::async_logger::OverflowPolicy::DropOldest)
+        .build()
+        .unwrap();
+    for i in 0..100 {
+        log_message(&logger, Level::Info, &format!("Async {}", i));
+    }
+    drop(logger);
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with("[lib] [INFO] Async 99\n"));
+}
+
+#[test]
+fn metrics() {
+    let path = log_file("metrics");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .rate_limit(Level::Warn, 3)
+        .build()
+        .unwrap();
+    let metrics = logger.metrics();
+    for _ in 0..10 {
+        log_message(&logger, Level::Error, "Error");
+        log_message(&logger, Level::Warn, "Warning");
+    }
+
+    assert_eq!(metrics.errors_logged(), 10);
+    assert_eq!(metrics.warns_logged(), 3);
+    assert_eq!(metrics.infos_logged(), 0);
+    assert_eq!(metrics.dropped(), 7);
+}
+
+#[test]
+fn multiline_indent() {
+    let path = log_file("multiline-indent");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp_format("%H:%M:%S")
+        .thread_id(true)
+        .multiline_indent(true)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "First\nSecond\nThird");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(lines.len(), 3);
+    let width = lines[0].find("First").unwrap();
+    assert_eq!(lines[1], format!("{:1$}Second", "", width));
+    assert_eq!(lines[2], format!("{:1$}Third", "", width));
+}
+
+#[test]
+fn module_display() {
+    let path = log_file("module-display");
+    for display in &[
+        mowl::ModuleDisplay::Full,
+        mowl::ModuleDisplay::Short,
+        mowl::ModuleDisplay::None,
+    ] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .module_display(display.clone())
+            .build()
+            .unwrap();
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Module"))
+                .level(Level::Info)
+                .module_path(Some("mowl::networking::http"))
+                .build(),
+        );
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[mowl::networking::http] [INFO] Module\n[http] [INFO] Module\n[INFO] Module\n"
+    );
+}
+
+#[test]
+fn max_message_length() {
+    let path = log_file("max-message-length");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .max_message_length(100)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, &"x".repeat(10_000));
+    log_message(&logger, Level::Info, &"ä".repeat(100));
+    log_message(&logger, Level::Info, "Short");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let messages: Vec<_> = content
+        .lines()
+        .map(|l| l.trim_start_matches("[lib] [INFO] "))
+        .collect();
+    assert_eq!(messages[0], format!("{}…", "x".repeat(100)));
+    assert!(messages[0].len() <= 100 + "…".len());
+    assert_eq!(messages[1], format!("{}…", "ä".repeat(50)));
+    assert_eq!(messages[2], "Short");
+
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .max_message_length(3)
+        .truncation_suffix("...")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "ääää");
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with("[lib] [INFO] ä...\n"));
+}
+
+#[test]
+fn show_pid() {
+    let path = log_file("show-pid");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .show_pid(true)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Process");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        format!("[{}] [lib] [INFO] Process\n", process::id())
+    );
+}
+
+#[test]
+fn app_name() {
+    let path = log_file("app-name");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp_mode(mowl::TimestampMode::Elapsed)
+        .app_name("myapp")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Labeled");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Unlabeled");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines: Vec<_> = content.lines().collect();
+    assert!(lines[0].starts_with("[+") && lines[0].ends_with("s] [myapp] [lib] [INFO] Labeled"));
+    assert_eq!(lines[1], "[lib] [INFO] Unlabeled");
+}
+
+#[test]
+fn csv_format() {
+    let path = log_file("csv-format");
+    for _ in 0..2 {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .format(mowl::Format::Csv)
+            .timestamp(false)
+            .color_mode(mowl::ColorMode::Always)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Warn, "Plain");
+        log_message(&logger, Level::Info, "With \"quotes\", commas\nand lines");
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    let record = "WARN,lib,Plain\nINFO,lib,\"With \"\"quotes\"\", commas\nand lines\"\n";
+    assert_eq!(
+        content,
+        format!("level,module,message\n{}{}", record, record)
+    );
+}
+
+#[test]
+fn target_display() {
+    let path = log_file("target-display");
+    for display in &[
+        mowl::TargetDisplay::Module,
+        mowl::TargetDisplay::Target,
+        mowl::TargetDisplay::Both,
+    ] {
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .target_display(*display)
+            .build()
+            .unwrap();
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Target"))
+                .level(Level::Info)
+                .target("my_target")
+                .module_path(Some("lib"))
+                .build(),
+        );
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[lib] [INFO] Target\n[my_target] [INFO] Target\n[my_target/lib] [INFO] Target\n"
+    );
+}
+
+#[test]
+fn chained_logger() {
+    let primary = log_file("chained-logger-primary");
+    let fallback = log_file("chained-logger-fallback");
+    let build = |path: &PathBuf| -> Box<dyn Log> {
+        Box::new(
+            mowl::LoggerBuilder::new()
+                .file(path)
+                .timestamp(false)
+                .build()
+                .unwrap(),
+        )
+    };
+    let message = Record::builder()
+        .args(format_args!("Chained"))
+        .level(Level::Info)
+        .module_path(Some("lib"))
+        .build();
+    mowl::ChainedLogger::new(build(&primary), build(&fallback), false).log(&message);
+    mowl::ChainedLogger::new(build(&primary), build(&fallback), true).log(&message);
+    mowl::ChainedLogger::new(build(&primary), build(&fallback), true).log(&message);
+
+    assert_eq!(fs::read_to_string(&primary).unwrap().lines().count(), 1);
+    assert_eq!(fs::read_to_string(&fallback).unwrap().lines().count(), 2);
+}
+
+#[test]
+fn level_alias() {
+    let path = log_file("level-alias");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .level(LevelFilter::Debug)
+        .timestamp(false)
+        .level_alias(Level::Debug, Level::Info)
+        .level_alias(Level::Trace, Level::Info)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Debug, "Promoted");
+    log_message(&logger, Level::Trace, "Filtered");
+    log_message(&logger, Level::Warn, "Unchanged");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [INFO] Promoted\n[lib] [WARN] Unchanged\n");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn message_filters() {
+    let path = log_file("message-filters");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .filter_in("^request")
+        .filter_in("^response")
+        .filter_out("health")
+        .build()
+        .unwrap();
+    for message in &["request /users", "request /health", "response 200", "other"] {
+        log_message(&logger, Level::Info, message);
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        "[lib] [INFO] request /users\n[lib] [INFO] response 200\n"
+    );
+    match mowl::LoggerBuilder::new().filter_out("(").build() {
+        Err(mowl::Error::Regex(_)) => {}
+        _ => panic!("Expected a regex error"),
+    }
+}
+
+#[test]
+fn long_formatted_message() {
+    let path = log_file("long-message");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let message = "x".repeat(300);
+    log_message(&logger, Level::Info, &message);
+    log_message(&logger, Level::Info, "short");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        format!("[lib] [INFO] {}\n[lib] [INFO] short\n", message)
+    );
+}
+
+#[test]
+fn timestamp_precision() {
+    for (precision, len) in &[
+        (mowl::TimestampPrecision::Date, 10),
+        (mowl::TimestampPrecision::DateTime, 19),
+        (mowl::TimestampPrecision::Microseconds, 27),
+        (mowl::TimestampPrecision::Nanoseconds, 30),
+    ] {
+        let path = log_file("timestamp-precision");
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp_precision(*precision)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Info, "Precise");
+
+        let content = fs::read_to_string(&path).unwrap();
+        let (timestamp, rest) = content.split_at(len + 3);
+        assert_eq!(timestamp.matches('-').count(), 2);
+        assert_eq!(timestamp.matches(':').count(), (*len - 10).min(9) / 4);
+        assert_eq!(timestamp.ends_with("Z] "), *len > 19);
+        assert_eq!(rest, "[lib] [INFO] Precise\n");
+    }
+}
+
+#[test]
+fn udp_output() {
+    let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Udp(server.local_addr().unwrap()))
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "First");
+    log_message(&logger, Level::Info, &"x".repeat(70_000));
+
+    let mut buffer = [0; 70_000];
+    let len = server.recv(&mut buffer).unwrap();
+    assert_eq!(&buffer[..len], b"[lib] [WARN] First\n");
+    let len = server.recv(&mut buffer).unwrap();
+    assert_eq!(len, 65_507);
+    assert!(buffer.starts_with(b"[lib] [INFO] xxx"));
+}
+
+#[test]
+fn tcp_output() {
+    use std::io::{BufRead, BufReader};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Tcp(listener.local_addr().unwrap()))
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "First");
+    log_message(&logger, Level::Info, "Second");
+
+    let (stream, _) = listener.accept().unwrap();
+    let lines: Vec<_> = BufReader::new(stream)
+        .lines()
+        .take(2)
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(lines, ["[lib] [WARN] First", "[lib] [INFO] Second"]);
+}
+
+#[cfg(all(unix, feature = "unix"))]
+#[test]
+fn named_pipe_output() {
+    use std::io::{BufRead, BufReader};
+
+    let path = log_file("named-pipe");
+    assert!(process::Command::new("mkfifo")
+        .arg(&path)
+        .status()
+        .unwrap()
+        .success());
+    let reader_path = path.clone();
+    let reader = thread::spawn(move || {
+        let mut line = String::new();
+        BufReader::new(fs::File::open(reader_path).unwrap())
+            .read_line(&mut line)
+            .unwrap();
+        line
+    });
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::NamedPipe(path.clone()))
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Through the pipe");
+    assert_eq!(reader.join().unwrap(), "[lib] [INFO] Through the pipe\n");
+
+    // The reader went away, so the record is written to stderr instead
+    log_message(&logger, Level::Info, "After the pipe broke");
+    fs::remove_file(&path).unwrap();
+}
+
+#[cfg(all(windows, feature = "windows-event-log"))]
+#[test]
+fn windows_event_log() {
+    let logger = mowl::WindowsEventLogger::new("mowl", LevelFilter::Info).unwrap();
+    logger.log(
+        &Record::builder()
+            .args(format_args!("Reported as event"))
+            .level(Level::Warn)
+            .build(),
+    );
+}
+
+#[cfg(feature = "tracing")]
+#[test]
+fn tracing_subscriber() {
+    let path = log_file("tracing");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .level(LevelFilter::Info)
+        .build()
+        .unwrap();
+    let subscriber = mowl::tracing_compat::MowlSubscriber::new(logger);
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("request", id = 42);
+        let _guard = span.enter();
+        tracing::info!(target: "lib", status = 200, "Handled {}", "/users");
+        tracing::debug!("Filtered");
+    });
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [INFO] Handled /users status=200 id=42\n");
+}
+
+#[test]
+fn level_target() {
+    let path = log_file("level-target");
+    let info_path = log_file("level-target-info");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .level_target(Level::Info, mowl::Output::File(info_path.clone()))
+        .level_target(Level::Debug, mowl::Output::File(info_path.clone()))
+        .timestamp(false)
+        .level(LevelFilter::Debug)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Error, "Error");
+    log_message(&logger, Level::Info, "Info");
+    log_message(&logger, Level::Debug, "Debug");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [ERROR] Error\n");
+    let content = fs::read_to_string(&info_path).unwrap();
+    assert_eq!(content, "[lib] [INFO] Info\n[lib] [DEBUG] Debug\n");
+}
+
+#[test]
+fn pre_hooks() {
+    let path = log_file("pre-hooks");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .pre_hook(|record| !record.args().to_string().contains("password"))
+        .pre_hook(|record| record.level() != Level::Warn)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Logged");
+    log_message(&logger, Level::Info, "password=secret");
+    log_message(&logger, Level::Warn, "Dropped");
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content, "[lib] [INFO] Logged\n");
+}
+
+#[test]
+fn on_error() {
+    use std::sync::Mutex;
+
+    let alerts = Arc::new(Mutex::new(vec![]));
+    let sent = alerts.clone();
+    let logger = mowl::LoggerBuilder::new()
+        .file(log_file("on-error"))
+        .on_error(move |message| sent.lock().unwrap().push(message.to_owned()))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "No alert");
+    log_message(&logger, Level::Error, "Disk full");
+
+    assert_eq!(*alerts.lock().unwrap(), ["Disk full"]);
+}
+
+#[cfg(feature = "gelf")]
+#[test]
+fn gelf_format() {
+    let server = std::net::UdpSocket::bind("127.0.0.1:0").unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Udp(server.local_addr().unwrap()))
+        .format(mowl::Format::Gelf {
+            host: "example.org".to_owned(),
+        })
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "Short\nand full");
+    log_message(&logger, Level::Info, &"x".repeat(10_000));
+
+    let mut buffer = [0; 8192];
+    let len = server.recv(&mut buffer).unwrap();
+    let object: serde_json::Value = serde_json::from_slice(&buffer[..len]).unwrap();
+    assert_eq!(object["version"], "1.1");
+    assert_eq!(object["host"], "example.org");
+    assert_eq!(object["short_message"], "Short");
+    assert_eq!(object["full_message"], "Short\nand full");
+    assert!(object["timestamp"].as_f64().unwrap() > 0.0);
+    assert_eq!(object["level"], 4);
+    assert_eq!(object["_module"], "lib");
+
+    // Both messages contain the long text, which requires three chunks
+    let mut payload = vec![];
+    for sequence in 0..3 {
+        let len = server.recv(&mut buffer).unwrap();
+        assert_eq!(buffer[..2], [0x1e, 0x0f]);
+        assert_eq!(buffer[10..12], [sequence, 3]);
+        payload.extend_from_slice(&buffer[12..len]);
+    }
+    let object: serde_json::Value = serde_json::from_slice(&payload).unwrap();
+    assert_eq!(object["level"], 6);
+}
+
+#[test]
+fn verbosity() {
+    use mowl::Verbosity;
+
+    let levels: Vec<_> = (0..6).map(LevelFilter::from_verbosity).collect();
+    assert_eq!(
+        levels,
+        [
+            LevelFilter::Error,
+            LevelFilter::Warn,
+            LevelFilter::Info,
+            LevelFilter::Debug,
+            LevelFilter::Trace,
+            LevelFilter::Trace,
+        ]
+    );
+}
+
+#[test]
+fn independent_loggers() {
+    let first_path = log_file("independent-first");
+    let second_path = log_file("independent-second");
+    let first =
+        mowl::Logger::new(LevelFilter::Info, mowl::Output::File(first_path.clone())).unwrap();
+    let second =
+        mowl::Logger::new(LevelFilter::Warn, mowl::Output::File(second_path.clone())).unwrap();
+    for logger in &[&first, &second] {
+        logger.log_message(Level::Info, "direct", "Info").unwrap();
+        logger.log_message(Level::Warn, "direct", "Warn").unwrap();
+    }
+
+    let content = fs::read_to_string(&first_path).unwrap();
+    assert!(content.contains("[direct] [INFO] Info\n"));
+    assert_eq!(content.lines().count(), 2);
+    let content = fs::read_to_string(&second_path).unwrap();
+    assert_eq!(content.lines().count(), 1);
+    assert!(content.ends_with("[direct] [WARN] Warn\n"));
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn cbor_format() {
+    use ciborium::value::Value;
+
+    let path = log_file("cbor");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .format(mowl::Format::Cbor)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "First");
+    log_message(&logger, Level::Info, "Second");
+
+    let content = fs::read(&path).unwrap();
+    let mut reader = content.as_slice();
+    for (level, message) in &[("WARN", "First"), ("INFO", "Second")] {
+        let value: Value = ciborium::de::from_reader(&mut reader).unwrap();
+        let map: Vec<_> = value
+            .into_map()
+            .unwrap()
+            .into_iter()
+            .map(|(k, v)| (k.into_text().unwrap(), v.into_text().unwrap()))
+            .collect();
+        assert_eq!(
+            map,
+            [
+                ("level".to_owned(), level.to_string()),
+                ("module".to_owned(), "lib".to_owned()),
+                ("message".to_owned(), message.to_string()),
+            ]
+        );
+    }
+    assert!(reader.is_empty());
+}
+
+#[test]
+fn rotating_file() {
+    let path = log_file("rotating");
+    let rotated = |index| PathBuf::from(format!("{}.{}", path.display(), index));
+    for index in 1..4 {
+        let _ = fs::remove_file(rotated(index));
+    }
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::RotatingFile {
+            path: path.clone(),
+            max_bytes: 10,
+            max_files: 2,
+        })
+        .build()
+        .unwrap();
+    for message in &["first", "second", "third", "fourth"] {
+        log_message(&logger, Level::Info, message);
+    }
+
+    assert!(fs::read_to_string(&path).unwrap().is_empty());
+    assert!(fs::read_to_string(rotated(1)).unwrap().contains("fourth"));
+    assert!(fs::read_to_string(rotated(2)).unwrap().contains("third"));
+    assert!(!rotated(3).exists());
+}
+
+#[test]
+fn timed_rotating_file() {
+    let path = log_file("timed-rotating");
+    let historical = |date: &str| PathBuf::from(format!("{}.{}", path.display(), date));
+    for date in &["2000-01-01", "2000-01-02"] {
+        fs::write(historical(date), "").unwrap();
+    }
+    fs::write(&path, "yesterday\n").unwrap();
+    let yesterday = SystemTime::now() - Duration::from_secs(2 * 24 * 60 * 60);
+    fs::File::options()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_modified(yesterday)
+        .unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::TimedRotatingFile {
+            path: path.clone(),
+            max_days: 2,
+        })
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "today");
+
+    assert!(fs::read_to_string(&path).unwrap().contains("today"));
+    assert!(!historical("2000-01-01").exists());
+    assert!(historical("2000-01-02").exists());
+    let rotated = fs::read_dir(env::temp_dir())
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.to_string_lossy()
+                .starts_with(&format!("{}.20", path.display()))
+        })
+        .filter(|p| *p != historical("2000-01-02"))
+        .collect::<Vec<_>>();
+    assert_eq!(rotated.len(), 1);
+    assert_eq!(fs::read_to_string(&rotated[0]).unwrap(), "yesterday\n");
+    fs::remove_file(&rotated[0]).unwrap();
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn compressed_rotating_file() {
+    let path = log_file("compressed-rotating");
+    let rotated = |suffix: &str| PathBuf::from(format!("{}.{}", path.display(), suffix));
+    for suffix in &["1", "1.gz", "2.gz"] {
+        let _ = fs::remove_file(rotated(suffix));
+    }
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::RotatingFile {
+            path: path.clone(),
+            max_bytes: 10,
+            max_files: 2,
+        })
+        .compress(true)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "first");
+    log_message(&logger, Level::Info, "second");
+    // Dropping the logger waits for the running compression
+    drop(logger);
+
+    assert!(!rotated("1").exists());
+    let mut content = String::new();
+    flate2::read::GzDecoder::new(fs::File::open(rotated("1.gz")).unwrap())
+        .read_to_string(&mut content)
+        .unwrap();
+    assert!(content.contains("second"));
+    assert!(rotated("2.gz").exists());
+}
+
+#[test]
+fn field_order() {
+    use mowl::Field;
+    let path = log_file("field-order");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .app_name("app")
+        .field_order(vec![Field::Level, Field::Message, Field::Module])
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "Reordered");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[WARN] Reordered [lib]\n"
+    );
+
+    let path = log_file("field-order-colored");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .app_name("app")
+        .field_order(vec![Field::Level, Field::Message, Field::Module])
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "Reordered");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .app_name("app")
+        .field_order(vec![Field::AppName, Field::Message])
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Warn, "Named");
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "\x1b[93m\x1b[1m[WARN]\x1b[0m Reordered \x1b[94m[lib]\x1b[0m\n\
+         \x1b[95m[app]\x1b[0m Named\n"
+    );
+
+    for order in [vec![Field::Level], vec![Field::Message, Field::Message]] {
+        match mowl::LoggerBuilder::new().field_order(order).build() {
+            Err(mowl::Error::FieldOrder(_)) => {}
+            _ => panic!("expected an invalid field order"),
+        }
+    }
+}
+
+#[test]
+fn concurrent_records_not_interleaved() {
+    let path = log_file("interleave");
+    let logger = Arc::new(mowl::LoggerBuilder::new().file(&path).build().unwrap());
+    let threads = (0..10)
+        .map(|i| {
+            let logger = logger.clone();
+            thread::spawn(move || {
+                for j in 0..1000 {
+                    log_message(&logger, Level::Info, &format!("thread {} record {}", i, j));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 10_000);
+    for line in content.lines() {
+        assert!(!line.contains("]["), "interleaved line: {}", line);
+        assert!(line.contains("] [lib] [INFO] thread "));
+    }
+}
+
+#[test]
+fn format_elapsed() {
+    use mowl::format::format_elapsed;
+    assert_eq!(format_elapsed(Duration::from_secs(0)), "0.000s");
+    assert_eq!(format_elapsed(Duration::from_millis(999)), "0.999s");
+    assert_eq!(format_elapsed(Duration::from_millis(59_999)), "59.999s");
+    assert_eq!(format_elapsed(Duration::from_secs(60)), "1m 0s");
+    assert_eq!(format_elapsed(Duration::from_secs(3599)), "59m 59s");
+    assert_eq!(format_elapsed(Duration::from_secs(3600)), "1h 0m 0s");
+    assert_eq!(format_elapsed(Duration::from_secs(3661)), "1h 1m 1s");
+}
+
+#[test]
+fn parse_level() {
+    let levels = [
+        LevelFilter::Off,
+        LevelFilter::Error,
+        LevelFilter::Warn,
+        LevelFilter::Info,
+        LevelFilter::Debug,
+        LevelFilter::Trace,
+    ];
+    for (number, level) in levels.iter().enumerate() {
+        let name = level.as_str();
+        assert_eq!(mowl::parse_level(&number.to_string()).unwrap(), *level);
+        assert_eq!(mowl::parse_level(name).unwrap(), *level);
+        assert_eq!(mowl::parse_level(&name.to_lowercase()).unwrap(), *level);
+        let capitalized = name[..1].to_owned() + &name[1..].to_lowercase();
+        assert_eq!(mowl::parse_level(&capitalized).unwrap(), *level);
+    }
+    for invalid in &["", "6", "-1", "verbose"] {
+        match mowl::parse_level(invalid) {
+            Err(mowl::Error::Level(ref s)) if s == invalid => {}
+            _ => panic!("expected an invalid level"),
+        }
+    }
+    assert_eq!(
+        mowl::parse_directives("WARN,myapp=4"),
+        Ok(vec![
+            (None, LevelFilter::Warn),
+            (Some("myapp".to_owned()), LevelFilter::Debug)
+        ])
+    );
+}
+
+#[test]
+fn level_padding() {
+    for (padding, expected) in &[
+        (mowl::LevelPad::Left, "[INFO ] Info\n[ERROR] Error\n"),
+        (mowl::LevelPad::Right, "[ INFO] Info\n[ERROR] Error\n"),
+        (mowl::LevelPad::None, "[INFO] Info\n[ERROR] Error\n"),
+    ] {
+        let path = log_file("level-padding");
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timestamp(false)
+            .module_display(mowl::ModuleDisplay::None)
+            .level_padding(*padding)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Info, "Info");
+        log_message(&logger, Level::Error, "Error");
+        assert_eq!(fs::read_to_string(&path).unwrap(), *expected);
+    }
+}
+
+#[test]
+fn logger_debug_and_display() {
+    let logger = mowl::LoggerBuilder::new()
+        .level(LevelFilter::Debug)
+        .color_mode(mowl::ColorMode::Never)
+        .format(mowl::Format::Logfmt)
+        .build()
+        .unwrap();
+    assert_eq!(
+        logger.to_string(),
+        "mowl Logger (level=Debug, colors=off, format=logfmt)"
+    );
+    let debug = format!("{:?}", logger);
+    assert!(debug.starts_with("Logger { level: Debug, enable_colors: false, "));
+    assert!(debug.contains("format: Logfmt, "));
+    assert!(debug.contains("timestamp_mode: WallClock, "));
+
+    let logger = mowl::LoggerBuilder::new()
+        .color_mode(mowl::ColorMode::Always)
+        .build()
+        .unwrap();
+    assert_eq!(
+        logger.to_string(),
+        "mowl Logger (level=Trace, colors=on, format=text)"
+    );
+}
+
+#[test]
+fn timestamp_format_specifiers() {
+    let path = log_file("timestamp-format-specifiers");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp_format("%F|%Y-%m-%d|%T|%H:%M:%S|%z|%%")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Specifiers");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let timestamp = &content[1..content.find(']').unwrap()];
+    let fields: Vec<_> = timestamp.split('|').collect();
+    assert_eq!(fields.len(), 6);
+    assert_eq!(fields[0].len(), 10);
+    assert_eq!(fields[0], fields[1]);
+    assert_eq!(fields[2].len(), 8);
+    assert_eq!(fields[2], fields[3]);
+    assert_eq!(fields[4], "+0000");
+    assert_eq!(fields[5], "%");
+}
+
+#[test]
+#[cfg(any(feature = "chrono", not(feature = "time-compat")))]
+fn timestamp_format_fractional_seconds() {
+    let path = log_file("timestamp-format-fractional");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp_format("%Y-%m-%d %H:%M:%S%.3f|%.6f|%.9f")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Fractional");
+
+    let content = fs::read_to_string(&path).unwrap();
+    let timestamp = &content[1..content.find(']').unwrap()];
+    let fields: Vec<_> = timestamp.split('|').collect();
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields[0].len(), "2024-06-15 12:00:00.000".len());
+    assert_eq!(&fields[0][19..20], ".");
+    for (field, digits) in fields.iter().zip([4, 7, 10]) {
+        let fraction = &field[field.len() - digits..];
+        assert!(fraction.starts_with('.'));
+        assert!(fraction[1..].bytes().all(|b| b.is_ascii_digit()));
+    }
+}
+
+#[test]
+fn log_direct() {
+    let path = log_file("log-direct");
+    let logger = mowl::Logger::new(LevelFilter::Error, mowl::Output::File(path.clone())).unwrap();
+    logger
+        .log_direct(Level::Debug, "direct", "Bypassed")
+        .unwrap();
+    logger
+        .log_message(Level::Debug, "direct", "Filtered")
+        .unwrap();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.ends_with("[direct] [DEBUG] Bypassed\n"));
+    assert_eq!(content.lines().count(), 1);
+}
+
+#[test]
+fn timezone() {
+    for timezone in &[mowl::TimeZone::Utc, mowl::TimeZone::Local] {
+        let path = log_file("timezone");
+        let logger = mowl::LoggerBuilder::new()
+            .file(&path)
+            .timezone(*timezone)
+            .build()
+            .unwrap();
+        log_message(&logger, Level::Info, "Zoned");
+
+        let content = fs::read_to_string(&path).unwrap();
+        let timestamp = &content[1..content.find(']').unwrap()];
+        assert_eq!(timestamp.len(), 25);
+        if *timezone == mowl::TimeZone::Utc {
+            assert!(timestamp.ends_with("+00:00"));
+        }
+    }
+}
+
+#[test]
+fn omit_root_module() {
+    let path = log_file("omit-root-module");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .omit_root_module("lib")
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Root");
+    logger.log_message(Level::Info, "lib::sub", "Sub").unwrap();
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[INFO] Root\n[lib::sub] [INFO] Sub\n"
+    );
+}
+
+#[test]
+fn init_testing() {
+    for _ in 0..2 {
+        let logger = mowl::init_testing(LevelFilter::Warn);
+        let record = Record::builder()
+            .args(format_args!("Tested"))
+            .level(Level::Warn)
+            .module_path(Some("lib"))
+            .build();
+        assert!(logger.enabled(record.metadata()));
+        assert!(!logger.enabled(&Metadata::builder().level(Level::Info).build()));
+        logger.log_result(&record).unwrap();
+    }
+}
+
+struct PlainFormatter;
+
+impl mowl::LogFormatter for PlainFormatter {
+    fn format(&self, record: &Record, sink: &mut dyn std::io::Write) -> Result<(), mowl::Error> {
+        writeln!(sink, "{}: {}", record.level(), record.args())?;
+        Ok(())
+    }
+}
+
+#[test]
+fn custom_formatter() {
+    let path = log_file("custom-formatter");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .app_name("app")
+        .formatter(Box::new(PlainFormatter))
+        .build()
+        .unwrap();
+    assert!(logger.to_string().ends_with("format=custom)"));
+    log_message(&logger, Level::Warn, "Custom");
+    assert_eq!(fs::read_to_string(&path).unwrap(), "WARN: Custom\n");
+
+    let path = log_file("text-formatter");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .formatter(Box::new(mowl::TextFormatter))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Text");
+    let content = fs::read_to_string(&path).unwrap();
+    assert!(content.starts_with('['));
+    assert!(content.ends_with("+00:00] [lib] [INFO] Text\n"));
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_formatter() {
+    let path = log_file("json-formatter");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .formatter(Box::new(mowl::JsonFormatter))
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Error, "Json");
+    let content = fs::read_to_string(&path).unwrap();
+    let object: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(object["level"], "ERROR");
+    assert_eq!(object["module"], "lib");
+    assert_eq!(object["message"], "Json");
+    assert!(object["timestamp"].is_string());
+}
+
+#[test]
+fn public_sink() {
+    use mowl::LogFormatter;
+    use std::io::Write;
+    let path = log_file("public-sink");
+    let mut sink = mowl::LogSink::new_file(&path).unwrap();
+    sink.fg(term::color::RED).unwrap();
+    write!(sink, "Plain").unwrap();
+    sink.reset().unwrap();
+    writeln!(sink).unwrap();
+    sink.flush().unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "Plain\n");
+
+    let mut sink = mowl::LogSink::new_stderr();
+    PlainFormatter
+        .format(
+            &Record::builder()
+                .args(format_args!("Formatted"))
+                .level(Level::Info)
+                .build(),
+            &mut sink,
+        )
+        .unwrap();
+
+    let missing = env::temp_dir().join("mowl-missing-dir").join("sink.log");
+    assert!(mowl::LogSink::new_file(missing).is_err());
+}
+
+#[test]
+fn error_display() {
+    let error = mowl::Error::from(std::io::Error::other("disk full"));
+    assert_eq!(format!("{}", error), "I/O error: disk full");
+    let error = mowl::Error::from(term::Error::NotSupported);
+    assert_eq!(
+        format!("{}", error),
+        "terminal error: operation not supported by the terminal"
+    );
+    let error = mowl::LoggerBuilder::new()
+        .file(env::temp_dir().join("mowl-missing-dir").join("error.log"))
+        .build()
+        .unwrap_err();
+    assert!(format!("{}", error).starts_with("parent directory '"));
+}
+
+#[test]
+fn clone_logger() {
+    let path = log_file("clone");
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let threads = (0..4)
+        .map(|i| {
+            let logger = logger.clone();
+            thread::spawn(move || log_message(&logger, Level::Info, &format!("Thread {}", i)))
+        })
+        .collect::<Vec<_>>();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    let clone = logger.clone();
+    clone.set_level(LevelFilter::Warn);
+    log_message(&logger, Level::Info, "Filtered");
+    logger.flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(content.lines().count(), 4);
+    for i in 0..4 {
+        assert!(content.contains(&format!("[lib] [INFO] Thread {}\n", i)));
+    }
+    assert_eq!(clone.metrics().logged(Level::Info), 4);
+}
+
+#[test]
+fn tcp_output_backoff() {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        time::Instant,
+    };
+
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .output(mowl::Output::Tcp(addr))
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let start = Instant::now();
+    assert!(logger.log_message(Level::Info, "lib", "Refused").is_err());
+    let error = logger
+        .log_message(Level::Info, "lib", "Dropped")
+        .unwrap_err();
+    assert!(error.to_string().contains("unreachable"));
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    let listener = TcpListener::bind(addr).unwrap();
+    thread::sleep(Duration::from_millis(150));
+    logger
+        .log_message(Level::Info, "lib", "Reconnected")
+        .unwrap();
+    let (stream, _) = listener.accept().unwrap();
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line).unwrap();
+    assert_eq!(line, "[lib] [INFO] Reconnected\n");
+}
+
+#[test]
+fn csv_header_per_output() {
+    let existing = log_file("csv-header-existing");
+    let new = log_file("csv-header-new");
+    fs::write(&existing, "level,module,message\nWARN,lib,Old\n").unwrap();
+    let logger = mowl::LoggerBuilder::new()
+        .file(&existing)
+        .add_sink(mowl::Output::File(new.clone()))
+        .format(mowl::Format::Csv)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    log_message(&logger, Level::Info, "Appended");
+
+    assert_eq!(
+        fs::read_to_string(&existing).unwrap(),
+        "level,module,message\nWARN,lib,Old\nINFO,lib,Appended\n"
+    );
+    assert_eq!(
+        fs::read_to_string(&new).unwrap(),
+        "level,module,message\nINFO,lib,Appended\n"
+    );
+}
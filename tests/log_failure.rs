@@ -0,0 +1,97 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Log, Record};
+use std::{
+    env,
+    io::{self, Write},
+    process::Command,
+};
+
+struct BrokenWriter;
+
+impl Write for BrokenWriter {
+    fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+        Err(io::Error::other("broken"))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A writer which accepts the records but cannot flush them.
+struct UnflushableWriter;
+
+impl Write for UnflushableWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Err(io::Error::other("unflushable"))
+    }
+}
+
+fn run_child(test: &str) -> (String, String) {
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["--exact", test, "--nocapture"])
+        .env("MOWL_FAILURE_CHILD", "1")
+        .output()
+        .unwrap();
+    (
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn log_failure() {
+    if env::var_os("MOWL_FAILURE_CHILD").is_some() {
+        let logger = mowl::LoggerBuilder::new()
+            .output(mowl::Output::Writer(mowl::SharedWriter::new(BrokenWriter)))
+            .build()
+            .unwrap();
+        for _ in 0..10 {
+            logger.log(
+                &Record::builder()
+                    .args(format_args!("Lost"))
+                    .level(Level::Error)
+                    .module_path(Some("myapp"))
+                    .build(),
+            );
+        }
+        return;
+    }
+
+    let (stdout, stderr) = run_child("log_failure");
+    assert!(!stdout.contains("[mowl]"));
+    assert_eq!(
+        stderr
+            .matches("[mowl] failed to log [ERROR/myapp]: ")
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn flush_failure() {
+    if env::var_os("MOWL_FAILURE_CHILD").is_some() {
+        let logger = mowl::LoggerBuilder::new()
+            .output(mowl::Output::Writer(mowl::SharedWriter::new(
+                UnflushableWriter,
+            )))
+            .build()
+            .unwrap();
+        for _ in 0..10 {
+            logger.flush();
+        }
+        return;
+    }
+
+    let (stdout, stderr) = run_child("flush_failure");
+    assert!(!stdout.contains("[mowl]"));
+    assert_eq!(stderr.matches("[mowl] failed to flush: ").count(), 1);
+}
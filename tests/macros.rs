@@ -0,0 +1,39 @@
+#[macro_use]
+extern crate mowl;
+
+use mowl::testing::CapturedRecord;
+
+mod inner {
+    pub fn log() {
+        debug!("Inner");
+    }
+}
+
+#[test]
+fn macros() {
+    let logger = mowl::testing::init();
+
+    warn!("Warning {}", 1);
+    info!(target: "custom", "Information");
+    log!(log::Level::Error, "Error");
+    inner::log();
+
+    let records: Vec<_> = logger
+        .drain()
+        .into_iter()
+        .map(
+            |CapturedRecord {
+                 module, message, ..
+             }| (module, message),
+        )
+        .collect();
+    assert_eq!(
+        records,
+        [
+            ("macros".to_owned(), "Warning 1".to_owned()),
+            ("macros".to_owned(), "Information".to_owned()),
+            ("macros".to_owned(), "Error".to_owned()),
+            ("macros::inner".to_owned(), "Inner".to_owned()),
+        ]
+    );
+}
@@ -0,0 +1,55 @@
+#![cfg(all(unix, feature = "unix", not(feature = "noop")))]
+extern crate log;
+extern crate mowl;
+
+use log::Level;
+use std::{
+    env, fs,
+    io::{BufRead, BufReader},
+    process::Command,
+    thread,
+};
+
+#[test]
+fn broken_pipe_large_record() {
+    if let Some(path) = env::var_os("MOWL_PIPE_CHILD") {
+        let reader_path = path.clone();
+        let reader = thread::spawn(move || {
+            let mut line = String::new();
+            BufReader::new(fs::File::open(reader_path).unwrap())
+                .read_line(&mut line)
+                .unwrap();
+        });
+        let logger = mowl::LoggerBuilder::new()
+            .output(mowl::Output::NamedPipe(path.into()))
+            .timestamp(false)
+            .color_mode(mowl::ColorMode::Never)
+            .build()
+            .unwrap();
+        logger
+            .log_message(Level::Info, "pipe", "Through the pipe")
+            .unwrap();
+        reader.join().unwrap();
+
+        // Larger than the buffer of the pipe, so it is written through
+        let large = "x".repeat(64 * 1024);
+        logger.log_message(Level::Info, "pipe", &large).unwrap();
+        return;
+    }
+
+    let path = env::temp_dir().join(format!("mowl-named-pipe-{}", std::process::id()));
+    assert!(Command::new("mkfifo")
+        .arg(&path)
+        .status()
+        .unwrap()
+        .success());
+    let output = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "broken_pipe_large_record", "--nocapture"])
+        .env("MOWL_PIPE_CHILD", &path)
+        .output()
+        .unwrap();
+    fs::remove_file(&path).unwrap();
+    assert!(output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(&format!("[pipe] [INFO] {}\n", "x".repeat(64 * 1024))));
+}
@@ -0,0 +1,55 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Log, Record};
+use std::{env, process::Command};
+
+/// Logs a record to the stderr pipe of a child process, whose colors are
+/// forced by `MOWL_FORCE_COLOR` unless disabled by the environment.
+fn log_to_stderr(mode: &str, vars: &[(&str, &str)]) -> String {
+    let mut command = Command::new(env::current_exe().unwrap());
+    command
+        .args(["--exact", "no_color", "--nocapture"])
+        .env("MOWL_NO_COLOR_CHILD", mode)
+        .env("MOWL_FORCE_COLOR", "1")
+        .env("TERM", "xterm")
+        .env_remove("NO_COLOR");
+    for (key, value) in vars {
+        command.env(key, value);
+    }
+    String::from_utf8(command.output().unwrap().stderr).unwrap()
+}
+
+#[test]
+fn no_color() {
+    if let Some(mode) = env::var_os("MOWL_NO_COLOR_CHILD") {
+        let mode = if mode == "always" {
+            mowl::ColorMode::Always
+        } else {
+            mowl::ColorMode::Auto
+        };
+        let logger = mowl::LoggerBuilder::new()
+            .timestamp(false)
+            .color_mode(mode)
+            .build()
+            .unwrap();
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Suppressed"))
+                .level(Level::Warn)
+                .module_path(Some("no_color"))
+                .build(),
+        );
+        return;
+    }
+
+    let plain = "[no_color] [WARN] Suppressed\n";
+    assert!(log_to_stderr("auto", &[]).contains("\x1b[1m[WARN]"));
+    assert_eq!(log_to_stderr("auto", &[("NO_COLOR", "1")]), plain);
+    assert_eq!(log_to_stderr("auto", &[("TERM", "dumb")]), plain);
+
+    // An explicit color mode takes precedence over the environment
+    assert!(log_to_stderr("always", &[("NO_COLOR", "1")]).contains("\x1b[1m[WARN]"));
+}
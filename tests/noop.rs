@@ -0,0 +1,15 @@
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, LevelFilter};
+
+#[test]
+fn init_noop() {
+    mowl::init_noop().unwrap();
+    assert!(mowl::init_noop().is_err());
+
+    assert_eq!(log::max_level(), LevelFilter::Off);
+    assert!(!log_enabled!(Level::Error));
+    error!("Discarded");
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "noop")]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, LevelFilter, Metadata, Record};
+use std::{env, fs, process};
+
+#[test]
+fn noop_feature() {
+    let path = env::temp_dir().join(format!("mowl-noop-feature-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    mowl::init_with_file(&path, LevelFilter::Trace).unwrap();
+    error!("Discarded");
+
+    assert_eq!(log::max_level(), LevelFilter::Off);
+    assert!(!log::logger().enabled(&Metadata::builder().level(Level::Error).build()));
+    assert!(!path.exists());
+
+    let logger = mowl::LoggerBuilder::new().file(&path).build().unwrap();
+    let record = Record::builder()
+        .args(format_args!("Discarded"))
+        .level(Level::Error)
+        .build();
+    logger.log_result(&record).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "");
+}
@@ -0,0 +1,30 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate mowl;
+
+use std::{env, fs, panic, process};
+
+#[test]
+fn panic_hook() {
+    let path = env::temp_dir().join(format!("mowl-panic-hook-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    mowl::LoggerBuilder::new()
+        .file(&path)
+        .colors(false)
+        .timestamp(false)
+        .init()
+        .unwrap();
+    mowl::install_panic_hook();
+
+    let line = line!() + 1;
+    assert!(panic::catch_unwind(|| panic!("Boom {}", 42)).is_err());
+
+    let content = fs::read_to_string(&path).unwrap();
+    assert_eq!(
+        content,
+        format!(
+            "[panic] [ERROR] thread 'panic_hook' panicked at tests/panic_hook.rs:{}: Boom 42\n",
+            line
+        )
+    );
+}
@@ -0,0 +1,24 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::LevelFilter;
+
+#[test]
+fn ring_buffer() {
+    let capacity = 5;
+    let (result, records) = mowl::ring_buffer::init(capacity, LevelFilter::Info);
+    result.unwrap();
+
+    for i in 0..capacity + 10 {
+        info!("Record {}", i);
+    }
+
+    let records = records.lock().unwrap();
+    assert_eq!(records.len(), capacity);
+    for (record, i) in records.iter().zip(10..) {
+        assert!(record.ends_with(&format!("[ring_buffer] [INFO] Record {}", i)));
+    }
+}
@@ -0,0 +1,33 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use std::{env, fs, process};
+
+#[test]
+fn set_colors() {
+    let path = env::temp_dir().join(format!("mowl-set-colors-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .color_mode(mowl::ColorMode::Always)
+        .init()
+        .unwrap();
+
+    warn!("Colored");
+    mowl::set_colors(false);
+    warn!("Plain");
+    mowl::set_colors(true);
+    warn!("Colored again");
+    log::logger().flush();
+
+    let content = fs::read_to_string(&path).unwrap();
+    let lines: Vec<_> = content.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].contains('\x1b'));
+    assert_eq!(lines[1], "[set_colors] [WARN] Plain");
+    assert!(lines[2].contains('\x1b'));
+}
@@ -0,0 +1,55 @@
+#![cfg(all(unix, feature = "unix"))]
+extern crate log;
+extern crate mowl;
+extern crate signal_hook;
+
+use log::LevelFilter;
+use signal_hook::consts::{SIGUSR1, SIGUSR2};
+use std::{env, fs, process, thread, time::Duration};
+
+/// Raises the signal and waits until the handler changed the level.
+fn raise(signal: i32, expected: LevelFilter) {
+    signal_hook::low_level::raise(signal).unwrap();
+    for _ in 0..100 {
+        if log::max_level() == expected {
+            return;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    panic!("Level did not change to {}", expected);
+}
+
+#[test]
+fn signal_handler() {
+    let path = env::temp_dir().join(format!("mowl-signal-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    mowl::LoggerBuilder::new()
+        .file(&path)
+        .timestamp(false)
+        .level(LevelFilter::Debug)
+        .init()
+        .unwrap();
+    mowl::unix::install_signal_handler().unwrap();
+
+    raise(SIGUSR1, LevelFilter::Trace);
+    raise(SIGUSR1, LevelFilter::Error);
+    raise(SIGUSR2, LevelFilter::Trace);
+    raise(SIGUSR2, LevelFilter::Debug);
+    raise(SIGUSR2, LevelFilter::Info);
+    raise(SIGUSR2, LevelFilter::Warn);
+
+    // The notices are logged after the level changed
+    let expected = "[mowl] [INFO] Log level changed to TRACE\n\
+                    [mowl] [INFO] Log level changed to ERROR\n\
+                    [mowl] [INFO] Log level changed to TRACE\n\
+                    [mowl] [INFO] Log level changed to DEBUG\n\
+                    [mowl] [INFO] Log level changed to INFO\n\
+                    [mowl] [INFO] Log level changed to WARN\n";
+    for _ in 0..100 {
+        if fs::read_to_string(&path).unwrap() == expected {
+            break;
+        }
+        thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(fs::read_to_string(&path).unwrap(), expected);
+}
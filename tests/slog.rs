@@ -0,0 +1,35 @@
+#![cfg(feature = "slog")]
+extern crate log;
+extern crate mowl;
+#[macro_use]
+extern crate slog;
+
+use log::LevelFilter;
+use slog::Drain;
+use std::{env, fs, process};
+
+#[test]
+fn slog_drain() {
+    let path = env::temp_dir().join(format!("mowl-slog-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    let logger = mowl::LoggerBuilder::new()
+        .level(LevelFilter::Info)
+        .file(&path)
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let root = slog::Logger::root(
+        mowl::slog_compat::MowlDrain::new(logger).fuse(),
+        o!("app" => "test"),
+    );
+
+    info!(root, "Handled"; "status" => 200);
+    crit!(root, "Critical");
+    debug!(root, "Filtered");
+
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "[slog] [INFO] Handled status=200 app=test\n\
+         [slog] [ERROR] Critical app=test\n"
+    );
+}
@@ -0,0 +1,88 @@
+// The records are discarded with the noop feature
+#![cfg(not(feature = "noop"))]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Log, Record};
+use mowl::{async_logger::OverflowPolicy, BufferMode, LoggerBuilder, Output, SharedWriter};
+use std::{
+    io::{self, Write},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+const RECORDS: usize = 20;
+
+/// A writer which takes 5ms for every write, like a slow network.
+#[derive(Clone, Default)]
+struct SlowWriter(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SlowWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        thread::sleep(Duration::from_millis(5));
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SlowWriter {
+    fn lines(&self) -> usize {
+        String::from_utf8(self.0.lock().unwrap().clone())
+            .unwrap()
+            .lines()
+            .count()
+    }
+}
+
+/// Logs the records and returns the time the calling thread was blocked.
+fn log_records(builder: LoggerBuilder, writer: &SlowWriter) -> Duration {
+    let logger = builder
+        .output(Output::Writer(SharedWriter::new(writer.clone())))
+        .timestamp(false)
+        .build()
+        .unwrap();
+    let start = Instant::now();
+    for i in 0..RECORDS {
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Record {}", i))
+                .level(Level::Info)
+                .module_path(Some("slow_sink"))
+                .build(),
+        );
+    }
+    let elapsed = start.elapsed();
+    logger.flush();
+    elapsed
+}
+
+#[test]
+fn synchronous_logging_blocks() {
+    let writer = SlowWriter::default();
+    let elapsed = log_records(LoggerBuilder::new(), &writer);
+    assert!(elapsed >= Duration::from_millis(5 * RECORDS as u64));
+    assert_eq!(writer.lines(), RECORDS);
+}
+
+#[test]
+fn async_logging_does_not_block() {
+    let writer = SlowWriter::default();
+    let builder = LoggerBuilder::new().async_dispatch(RECORDS, OverflowPolicy::Block);
+    let elapsed = log_records(builder, &writer);
+    assert!(elapsed < Duration::from_millis(5 * RECORDS as u64));
+    assert_eq!(writer.lines(), RECORDS);
+}
+
+#[test]
+fn buffered_logging_does_not_block() {
+    let writer = SlowWriter::default();
+    let builder = LoggerBuilder::new().buffer_mode(BufferMode::Block(64 * 1024));
+    let elapsed = log_records(builder, &writer);
+    assert!(elapsed < Duration::from_millis(5 * RECORDS as u64));
+    assert_eq!(writer.lines(), RECORDS);
+}
@@ -0,0 +1,26 @@
+#[macro_use]
+extern crate log;
+extern crate mowl;
+
+use log::Level;
+use std::sync::Arc;
+
+#[test]
+fn memory_logger() {
+    let logger = mowl::testing::init();
+    assert!(Arc::ptr_eq(&logger, &mowl::testing::init()));
+
+    info!("First");
+    error!(target: "custom", "Second");
+
+    let records = logger.records();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].level, Level::Info);
+    assert_eq!(records[0].module, "testing");
+    assert_eq!(records[0].message, "First");
+    assert_eq!(records[1].level, Level::Error);
+    assert_eq!(records[1].message, "Second");
+
+    assert_eq!(logger.drain(), records);
+    assert!(logger.records().is_empty());
+}
@@ -0,0 +1,73 @@
+#![cfg(feature = "validate-utf8")]
+extern crate log;
+extern crate mowl;
+
+use log::{Level, Record};
+use std::{env, fs, io::Write, process};
+
+/// A formatter which emits invalid UTF-8 for every record.
+struct InvalidFormatter;
+
+impl mowl::LogFormatter for InvalidFormatter {
+    fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), mowl::Error> {
+        write!(sink, "{} ", record.args())?;
+        sink.write_all(b"\xff\xfe\n")?;
+        Ok(())
+    }
+}
+
+/// A formatter which emits invalid UTF-8 for a single message only.
+struct RawFormatter;
+
+impl mowl::LogFormatter for RawFormatter {
+    fn format(&self, record: &Record, sink: &mut dyn Write) -> Result<(), mowl::Error> {
+        match record.args().to_string().as_str() {
+            "Invalid" => sink.write_all(b"Invalid \xff\xfe\n")?,
+            message => writeln!(sink, "{}", message)?,
+        }
+        Ok(())
+    }
+}
+
+fn log_with(formatter: Box<dyn mowl::LogFormatter>, name: &str, messages: &[&str]) -> String {
+    let path = env::temp_dir().join(format!("mowl-{}-{}.log", name, process::id()));
+    let _ = fs::remove_file(&path);
+    let logger = mowl::LoggerBuilder::new()
+        .file(&path)
+        .formatter(formatter)
+        .build()
+        .unwrap();
+    for message in messages {
+        logger.log_message(Level::Info, "utf8", message).unwrap();
+    }
+    fs::read_to_string(&path).unwrap()
+}
+
+#[test]
+fn validate_utf8() {
+    let content = log_with(
+        Box::new(RawFormatter),
+        "validate-utf8",
+        &["Valid", "Invalid"],
+    );
+    let mut lines = content.lines();
+    assert_eq!(lines.next(), Some("Valid"));
+    assert!(lines.next().unwrap().ends_with(
+        "+00:00] [utf8] [INFO] <invalid utf-8 in log message> \
+         49 6e 76 61 6c 69 64 20 ff fe 0a"
+    ));
+    assert_eq!(lines.next(), None);
+}
+
+#[test]
+fn validate_utf8_always_invalid() {
+    // Reading the file as string fails if the invalid bytes are written
+    let content = log_with(
+        Box::new(InvalidFormatter),
+        "validate-utf8-invalid",
+        &["Bad"],
+    );
+    assert!(
+        content.ends_with("[utf8] [INFO] <invalid utf-8 in log message> 42 61 64 20 ff fe 0a\n")
+    );
+}